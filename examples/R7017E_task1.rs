@@ -4,15 +4,15 @@ use matrs::predule::*;
 
 use robotics::{
     decore::decorators::{ToMatlab, ToTex},
-    pose::Pose,
+    pose::{Direction, Pose},
 };
 
 fn task4() {
     let p0_1 = Pose::<f32, 0, 1>::from_translation(Vector::new_from_data([3f32, 0f32, 0f32]));
     let p1_2 = Pose::<f32, 1, 2>::rot(rotz(PI / 2f32).unwrap());
-    let (rot, _t) = p1_2.clone().decompose();
-
-    let translation = rot.clone().transpose() * Vector::new_from_data([0f32, 1f32, 0f32]);
+    // The translation is given in frame 1, express it in frame 2
+    let translation = Direction::<f32, 1>::new(0f32, 1f32, 0f32);
+    let translation: Vector<f32, 3> = p1_2.inverse_transform(translation).into();
     let p2_3 = Pose::<f32, 2, 3>::from_translation(translation);
     println!("p2_3:\n{p2_3}");
 
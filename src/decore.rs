@@ -1,22 +1,83 @@
 use super::Matrix;
 
+pub mod matlab;
+mod mathml;
+mod names;
+pub mod table;
+mod typst;
+
 pub mod decorators {
     use std::fmt::Display;
 
     use matrs::{matrix::rotations::Trig, vec::Vector, CompliantNumerical};
 
-    use crate::pose::{Coord, Pose};
+    use crate::pose::{Direction, Point, Pose};
+    use crate::syms::Sym;
 
     use super::*;
     pub trait ToMatlab {
         fn to_matlab(&self, identifier: &'static str) -> String;
     }
+    /// Export to [MathML](https://www.w3.org/Math/) for HTML documents.
+    pub trait ToMathML {
+        fn to_mathml(&self, identifier: Option<&'static str>) -> String {
+            let id = match identifier {
+                Some(identifier) => format!(" id=\"{}\"", label(identifier)),
+                None => String::new(),
+            };
+            format!(
+                "<math display=\"block\"{id}>{}</math>",
+                self.to_mathml_internal(identifier)
+            )
+        }
+        fn to_mathml_internal(&self, identifier: Option<&'static str>) -> String;
+    }
+    /// Export to [Typst](https://typst.app) math.
+    pub trait ToTypst {
+        fn to_typst(&self, identifier: Option<&'static str>) -> String {
+            let ret = format!("$ {} $", self.to_typst_internal(identifier));
+            match identifier.map(label) {
+                Some(label) if !label.is_empty() => format!("{ret} <{label}>"),
+                _ => ret,
+            }
+        }
+        fn to_typst_internal(&self, identifier: Option<&'static str>) -> String;
+    }
+    /// Reduces an identifier such as `^0T_{1}` to something usable as a label, `0T_1`.
+    pub(crate) fn label(identifier: &str) -> String {
+        identifier
+            .chars()
+            .filter(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+            .collect()
+    }
+    /// Export to GNU Octave, symbolic content uses the `symbolic` package.
+    pub trait ToOctave {
+        fn to_octave(&self, identifier: &'static str) -> String;
+    }
+    /// Export to Julia, symbolic content uses `Symbolics.jl`.
+    pub trait ToJulia {
+        fn to_julia(&self, identifier: &'static str) -> String;
+    }
+    /// Scalars that can be written in the Octave and Julia exporters.
+    pub trait ScriptScalar {
+        fn to_octave_expr(&self) -> String;
+        fn to_julia_expr(&self) -> String;
+        /// Collects the identifiers that need to be declared before the value can be used.
+        fn identifiers(&self, _ret: &mut Vec<&'static str>) {}
+    }
     pub trait ToTex {
         fn to_tex(&self,identifier: Option<&'static str>) -> String{
             self.to_tex_internal(identifier,true)
         }
         fn to_tex_internal(&self, identifier: Option<&'static str>,first:bool) -> String;
     }
+    /// Lays out every element of a matrix as its own line in an `align` environment.
+    ///
+    /// Long elements are broken at top level `+`/`-` so that no line exceeds `width`
+    /// characters of TeX source, which keeps large symbolic poses on the page.
+    pub trait ToTexAlign {
+        fn to_tex_align(&self, identifier: Option<&'static str>, width: usize) -> String;
+    }
     impl<T: CompliantNumerical + Display, const M: usize, const N: usize> ToMatlab for Matrix<T, M, N> {
         fn to_matlab(&self, identifier: &'static str) -> String {
             let mut ret = format!("{identifier}\t=\t[");
@@ -64,6 +125,119 @@ pub mod decorators {
         }
     }
 
+    /// Trims surrounding whitespace without breaking a trailing control space (`\\ `).
+    fn trim_tex(tex: &str) -> String {
+        let ret = tex.trim();
+        match ret.ends_with('\\') {
+            true => format!("{ret} "),
+            false => ret.to_string(),
+        }
+    }
+
+    /// Splits a TeX expression at top level `+`/`-`, keeping the operator on the
+    /// term that follows it. Unary minus signs are left attached to their operand.
+    fn split_terms(tex: &str) -> Vec<String> {
+        let mut ret = Vec::new();
+        let mut depth: isize = 0;
+        let mut start = 0;
+        let mut prev: Option<char> = None;
+        let mut chars = tex.char_indices();
+        while let Some((idx, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    let rest = &tex[idx..];
+                    for (cmd, delta) in [("\\left", 1), ("\\right", -1)] {
+                        if rest.starts_with(cmd) {
+                            depth += delta;
+                            // Skip the command and the delimiter following it
+                            for _ in 0..cmd.len() {
+                                chars.next();
+                            }
+                        }
+                    }
+                    if rest.starts_with("\\cdot") {
+                        for _ in 0.."cdot".len() {
+                            chars.next();
+                        }
+                        prev = Some('*');
+                        continue;
+                    }
+                }
+                '{' | '(' | '[' => depth += 1,
+                '}' | ')' | ']' => depth -= 1,
+                '+' | '-' if depth == 0 => {
+                    let unary = matches!(prev, None | Some('+' | '-' | '*' | '/' | '=' | '&'));
+                    if !unary && !tex[start..idx].trim().is_empty() {
+                        ret.push(trim_tex(&tex[start..idx]));
+                        start = idx;
+                    }
+                }
+                _ => {}
+            }
+            if !c.is_whitespace() {
+                prev = Some(c);
+            }
+        }
+        ret.push(trim_tex(&tex[start..]));
+        ret
+    }
+
+    /// Packs the terms of an expression into lines of at most `width` characters.
+    ///
+    /// A single term longer than `width` is kept on its own line.
+    fn break_lines(tex: &str, width: usize) -> Vec<String> {
+        let mut lines: Vec<String> = Vec::new();
+        let mut curr = String::new();
+        for term in split_terms(tex) {
+            if !curr.is_empty() && curr.len() + term.len() + 1 > width {
+                lines.push(trim_tex(&curr));
+                curr = String::new();
+            }
+            if !curr.is_empty() {
+                curr += " ";
+            }
+            curr += term.as_str();
+        }
+        lines.push(trim_tex(&curr));
+        lines
+    }
+
+    impl<T: CompliantNumerical + ToTex, const M: usize, const N: usize> ToTexAlign
+        for Matrix<T, M, N>
+    {
+        fn to_tex_align(&self, identifier: Option<&'static str>, width: usize) -> String {
+            let name = identifier.unwrap_or("A");
+            let identifier = identifier.unwrap_or("");
+            let mut entries = Vec::new();
+            for (row_idx, row) in self.get_elements().iter().enumerate() {
+                for (col_idx, el) in row.iter().enumerate() {
+                    let lines = break_lines(el.to_tex(None).as_str(), width);
+                    entries.push(format!(
+                        "\t\\left({name}\\right)_{{{}{}}} &= {}",
+                        row_idx + 1,
+                        col_idx + 1,
+                        lines.join(" \\nonumber\\\\\n\t\t&\\quad ")
+                    ));
+                }
+            }
+            let mut ret = "\\begin{align}\n".to_string();
+            // Only the first element is numbered, the label refers to the matrix as a whole
+            for (idx, entry) in entries.iter().enumerate() {
+                ret += entry.as_str();
+                match idx {
+                    0 => ret += format!(" \\label{{ {identifier} }}").as_str(),
+                    _ => ret += " \\nonumber",
+                }
+                if idx + 1 < entries.len() {
+                    ret += "\\\\";
+                }
+                ret += "\n";
+            }
+            ret += "\\end{align}\n";
+            ret
+        }
+    }
+
     impl<'a, T: CompliantNumerical + Display, const M: usize, const N: usize> ToMatlab
         for &'a Matrix<T, M, N>
     {
@@ -92,7 +266,13 @@ pub mod decorators {
         }
     }
 
-    impl<T: CompliantNumerical + ToTex, const FRAME: usize> ToTex for Coord<T, FRAME> {
+    impl<T: CompliantNumerical + ToTex, const FRAME: usize> ToTex for Direction<T, FRAME> {
+        fn to_tex_internal(&self, identifier: Option<&'static str>,first:bool) -> String {
+            self.rpr.to_tex_internal(identifier,first)
+        }
+    }
+
+    impl<T: CompliantNumerical + ToTex, const FRAME: usize> ToTex for Point<T, FRAME> {
         fn to_tex_internal(&self, identifier: Option<&'static str>,first:bool) -> String {
             self.rpr.to_tex_internal(identifier,first)
         }
@@ -116,9 +296,251 @@ pub mod decorators {
             intermediate.to_tex_internal(identifier,first)
         }
     }
+    impl<T: CompliantNumerical + ToTex + Trig, const PREV: usize, const CURR: usize> ToTexAlign
+        for Pose<T, PREV, CURR>
+    {
+        fn to_tex_align(&self, identifier: Option<&'static str>, width: usize) -> String {
+            let intermediate: &Matrix<T, 4, 4> = self.into();
+
+            intermediate.to_tex_align(identifier, width)
+        }
+    }
     impl ToTex for f32 {
         fn to_tex_internal(&self, _identifier: Option<&'static str>,first:bool) -> String {
             self.to_string()
         }
     }
+
+    impl<T: CompliantNumerical + ToMathML, const M: usize, const N: usize> ToMathML
+        for Matrix<T, M, N>
+    {
+        fn to_mathml_internal(&self, identifier: Option<&'static str>) -> String {
+            let mut ret = "<mrow>".to_string();
+            if let Some(identifier) = identifier {
+                ret += format!("{}<mo>=</mo>", mathml::identifier(identifier)).as_str();
+            }
+            ret += "<mrow><mo>[</mo><mtable>";
+            for row in self.get_elements().iter() {
+                ret += "<mtr>";
+                for el in row.iter() {
+                    ret += format!("<mtd>{}</mtd>", el.to_mathml_internal(None)).as_str();
+                }
+                ret += "</mtr>";
+            }
+            ret += "</mtable><mo>]</mo></mrow></mrow>";
+            ret
+        }
+    }
+
+    impl<T: CompliantNumerical + ToTypst, const M: usize, const N: usize> ToTypst
+        for Matrix<T, M, N>
+    {
+        fn to_typst_internal(&self, identifier: Option<&'static str>) -> String {
+            let rows = self
+                .get_elements()
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|el| el.to_typst_internal(None))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                })
+                .collect::<Vec<String>>()
+                .join("; ");
+            match identifier {
+                Some(identifier) => {
+                    format!("{} = mat(delim: \"[\", {rows})", typst::identifier(identifier))
+                }
+                None => format!("mat(delim: \"[\", {rows})"),
+            }
+        }
+    }
+
+    impl<T: CompliantNumerical + ToMathML, const COUNT: usize> ToMathML for Vector<T, COUNT> {
+        fn to_mathml_internal(&self, identifier: Option<&'static str>) -> String {
+            self.clone().to_matrix().to_mathml_internal(identifier)
+        }
+    }
+
+    impl<T: CompliantNumerical + ToTypst, const COUNT: usize> ToTypst for Vector<T, COUNT> {
+        fn to_typst_internal(&self, identifier: Option<&'static str>) -> String {
+            self.clone().to_matrix().to_typst_internal(identifier)
+        }
+    }
+
+    impl<T: CompliantNumerical + ToMathML, const FRAME: usize> ToMathML for Direction<T, FRAME> {
+        fn to_mathml_internal(&self, identifier: Option<&'static str>) -> String {
+            self.rpr.to_mathml_internal(identifier)
+        }
+    }
+
+    impl<T: CompliantNumerical + ToMathML, const FRAME: usize> ToMathML for Point<T, FRAME> {
+        fn to_mathml_internal(&self, identifier: Option<&'static str>) -> String {
+            self.rpr.to_mathml_internal(identifier)
+        }
+    }
+
+    impl<T: CompliantNumerical + ToTypst, const FRAME: usize> ToTypst for Direction<T, FRAME> {
+        fn to_typst_internal(&self, identifier: Option<&'static str>) -> String {
+            self.rpr.to_typst_internal(identifier)
+        }
+    }
+
+    impl<T: CompliantNumerical + ToTypst, const FRAME: usize> ToTypst for Point<T, FRAME> {
+        fn to_typst_internal(&self, identifier: Option<&'static str>) -> String {
+            self.rpr.to_typst_internal(identifier)
+        }
+    }
+
+    impl<T: CompliantNumerical + ToMathML + Trig, const PREV: usize, const CURR: usize> ToMathML
+        for Pose<T, PREV, CURR>
+    {
+        fn to_mathml_internal(&self, identifier: Option<&'static str>) -> String {
+            let intermediate: &Matrix<T, 4, 4> = self.into();
+
+            intermediate.to_mathml_internal(identifier)
+        }
+    }
+
+    impl<T: CompliantNumerical + ToTypst + Trig, const PREV: usize, const CURR: usize> ToTypst
+        for Pose<T, PREV, CURR>
+    {
+        fn to_typst_internal(&self, identifier: Option<&'static str>) -> String {
+            let intermediate: &Matrix<T, 4, 4> = self.into();
+
+            intermediate.to_typst_internal(identifier)
+        }
+    }
+
+    impl ScriptScalar for f32 {
+        fn to_octave_expr(&self) -> String {
+            self.to_string()
+        }
+        fn to_julia_expr(&self) -> String {
+            self.to_string()
+        }
+    }
+    impl ScriptScalar for f64 {
+        fn to_octave_expr(&self) -> String {
+            self.to_string()
+        }
+        fn to_julia_expr(&self) -> String {
+            self.to_string()
+        }
+    }
+    impl ScriptScalar for Sym {
+        fn to_octave_expr(&self) -> String {
+            matlab::expr(self)
+        }
+        fn to_julia_expr(&self) -> String {
+            matlab::expr_with(self, "π")
+        }
+        fn identifiers(&self, ret: &mut Vec<&'static str>) {
+            matlab::identifiers(self, ret)
+        }
+    }
+
+    /// Identifiers in the matrix, as variable names.
+    fn declared<T: CompliantNumerical + ScriptScalar, const M: usize, const N: usize>(
+        m: &Matrix<T, M, N>,
+    ) -> Vec<String> {
+        let mut ret = Vec::new();
+        for row in m.get_elements().iter() {
+            for el in row.iter() {
+                el.identifiers(&mut ret);
+            }
+        }
+        ret.into_iter().map(matlab::identifier).collect()
+    }
+
+    impl<T: CompliantNumerical + ScriptScalar, const M: usize, const N: usize> ToOctave
+        for Matrix<T, M, N>
+    {
+        fn to_octave(&self, identifier: &'static str) -> String {
+            let mut ret = String::new();
+            let identifiers = declared(self);
+            if !identifiers.is_empty() {
+                ret += "pkg load symbolic\n";
+                ret += format!("syms {}\n", identifiers.join(" ")).as_str();
+            }
+            let rows = self
+                .get_elements()
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|el| el.to_octave_expr())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                })
+                .collect::<Vec<String>>()
+                .join("; ");
+            ret += format!("{identifier} = [{rows}];\n").as_str();
+            ret
+        }
+    }
+
+    impl<T: CompliantNumerical + ScriptScalar, const M: usize, const N: usize> ToJulia
+        for Matrix<T, M, N>
+    {
+        fn to_julia(&self, identifier: &'static str) -> String {
+            let mut ret = String::new();
+            let identifiers = declared(self);
+            if !identifiers.is_empty() {
+                ret += "using Symbolics\n";
+                ret += format!("@variables {}\n", identifiers.join(" ")).as_str();
+            }
+            let rows = self
+                .get_elements()
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|el| {
+                            // Elements are space separated, so `a -b` would be read as two elements
+                            let el = el.to_julia_expr();
+                            match el.contains(' ') {
+                                true => format!("({el})"),
+                                false => el,
+                            }
+                        })
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                })
+                .collect::<Vec<String>>()
+                .join("; ");
+            ret += format!("{identifier} = [{rows}]\n").as_str();
+            ret
+        }
+    }
+
+    impl<T: CompliantNumerical + ScriptScalar, const COUNT: usize> ToOctave for Vector<T, COUNT> {
+        fn to_octave(&self, identifier: &'static str) -> String {
+            self.clone().to_matrix().to_octave(identifier)
+        }
+    }
+
+    impl<T: CompliantNumerical + ScriptScalar, const COUNT: usize> ToJulia for Vector<T, COUNT> {
+        fn to_julia(&self, identifier: &'static str) -> String {
+            self.clone().to_matrix().to_julia(identifier)
+        }
+    }
+
+    impl<T: CompliantNumerical + ScriptScalar + Trig, const PREV: usize, const CURR: usize> ToOctave
+        for Pose<T, PREV, CURR>
+    {
+        fn to_octave(&self, identifier: &'static str) -> String {
+            let intermediate: &Matrix<T, 4, 4> = self.into();
+
+            intermediate.to_octave(identifier)
+        }
+    }
+
+    impl<T: CompliantNumerical + ScriptScalar + Trig, const PREV: usize, const CURR: usize> ToJulia
+        for Pose<T, PREV, CURR>
+    {
+        fn to_julia(&self, identifier: &'static str) -> String {
+            let intermediate: &Matrix<T, 4, 4> = self.into();
+
+            intermediate.to_julia(identifier)
+        }
+    }
 }
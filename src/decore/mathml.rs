@@ -0,0 +1,114 @@
+//! [`ToMathML`] for scalars.
+use crate::syms::{Constant, Operation, Sym};
+
+use super::decorators::ToMathML;
+use super::names::{self, Part};
+
+fn parts(parts: &[Part]) -> String {
+    let text = parts
+        .iter()
+        .map(|p| match p {
+            Part::Text(t) => t.clone(),
+            Part::Command(c) => names::greek(c).map(String::from).unwrap_or(c.clone()),
+        })
+        .collect::<String>();
+    match !text.is_empty() && text.chars().all(|c| c.is_ascii_digit()) {
+        true => format!("<mn>{text}</mn>"),
+        false => format!("<mi>{text}</mi>"),
+    }
+}
+
+/// Renders a TeX style identifier such as `\theta_1` or `^0T_1`.
+pub(crate) fn identifier(identifier: &str) -> String {
+    let name = names::parse(identifier);
+    let base = parts(&name.base);
+    let script = |p: &Option<Vec<Part>>| p.as_ref().map(|p| parts(p));
+    match (script(&name.pre), script(&name.sub), script(&name.sup)) {
+        (Some(pre), sub, sup) => format!(
+            "<mmultiscripts>{base}{}{}<mprescripts/><none/>{pre}</mmultiscripts>",
+            sub.unwrap_or("<none/>".to_string()),
+            sup.unwrap_or("<none/>".to_string())
+        ),
+        (None, Some(sub), Some(sup)) => format!("<msubsup>{base}{sub}{sup}</msubsup>"),
+        (None, Some(sub), None) => format!("<msub>{base}{sub}</msub>"),
+        (None, None, Some(sup)) => format!("<msup>{base}{sup}</msup>"),
+        (None, None, None) => base,
+    }
+}
+
+fn wrap(s: &Sym, min: u8) -> String {
+    match s.precedence() < min {
+        true => format!("<mrow><mo>(</mo>{}<mo>)</mo></mrow>", expr(s)),
+        false => expr(s),
+    }
+}
+
+fn join(els: &[&Sym], min: u8, op: &str) -> String {
+    els.iter()
+        .map(|s| wrap(s, min))
+        .collect::<Vec<String>>()
+        .join(format!("<mo>{op}</mo>").as_str())
+}
+
+fn function(name: &str, s: &Sym) -> String {
+    format!(
+        "<mrow><mi>{name}</mi><mo>&#x2061;</mo><mrow><mo>(</mo>{}<mo>)</mo></mrow></mrow>",
+        expr(s)
+    )
+}
+
+fn expr(s: &Sym) -> String {
+    match s {
+        Sym::Number(n) if *n < 0f32 => format!("<mrow><mo>-</mo><mn>{}</mn></mrow>", -n),
+        Sym::Number(n) => format!("<mn>{n}</mn>"),
+        Sym::Identifier(i) => identifier(i),
+        Sym::Constant(Constant::Pi) => "<mi>π</mi>".to_string(),
+        Sym::Operation(o) => match &**o {
+            Operation::Sqrt(s) => format!("<msqrt>{}</msqrt>", expr(s)),
+            Operation::Sin(s) => function("sin", s),
+            Operation::Cos(s) => function("cos", s),
+            Operation::Nop(s) => expr(s),
+            Operation::UnSub(s) => format!("<mrow><mo>-</mo>{}</mrow>", wrap(s, 3)),
+            Operation::Add(s1, s2) => format!("<mrow>{}</mrow>", join(&[s1, s2], 1, "+")),
+            Operation::Sum(els) => format!(
+                "<mrow>{}</mrow>",
+                join(&els.iter().collect::<Vec<&Sym>>(), 1, "+")
+            ),
+            Operation::Sub(s1, s2) => {
+                format!("<mrow>{}<mo>-</mo>{}</mrow>", wrap(s1, 1), wrap(s2, 2))
+            }
+            Operation::Mul(s1, s2) => format!("<mrow>{}</mrow>", join(&[s1, s2], 3, "&#x22C5;")),
+            Operation::Prod(els) => format!(
+                "<mrow>{}</mrow>",
+                join(&els.iter().collect::<Vec<&Sym>>(), 3, "&#x22C5;")
+            ),
+            Operation::Div(s1, s2) => format!(
+                "<mfrac><mrow>{}</mrow><mrow>{}</mrow></mfrac>",
+                expr(s1),
+                expr(s2)
+            ),
+            Operation::Rem(s1, s2) => {
+                format!("<mrow>{}<mo>mod</mo>{}</mrow>", wrap(s1, 3), wrap(s2, 4))
+            }
+        },
+    }
+}
+
+impl ToMathML for Sym {
+    fn to_mathml_internal(&self, identifier: Option<&'static str>) -> String {
+        match identifier {
+            Some(id) => format!(
+                "<mrow>{}<mo>=</mo>{}</mrow>",
+                self::identifier(id),
+                expr(self)
+            ),
+            None => expr(self),
+        }
+    }
+}
+
+impl ToMathML for f32 {
+    fn to_mathml_internal(&self, identifier: Option<&'static str>) -> String {
+        Sym::Number(*self).to_mathml_internal(identifier)
+    }
+}
@@ -0,0 +1,279 @@
+//! Export to runnable MATLAB scripts using the Symbolic Math Toolbox.
+//!
+//! Unlike [`ToMatlab`](super::decorators::ToMatlab), which only prints the numeric contents of a
+//! matrix, the scripts generated here declare every identifier with `syms`, build each transform
+//! and compose them, such that results can be cross-checked in MATLAB directly.
+use matrs::{matrix::rotations::Trig, CompliantNumerical};
+
+use crate::link::{Convention, DHTable};
+use crate::pose::Pose;
+use crate::syms::{Constant, Operation, Sym};
+
+use super::Matrix;
+
+/// Converts an identifier to a valid MATLAB variable name by stripping TeX markup, i.e.
+/// `\theta_{1}` becomes `theta_1`.
+pub fn identifier(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '\\' | '{' | '}'))
+        .collect()
+}
+
+fn wrap(s: &Sym, min: u8, pi: &str) -> String {
+    match s.precedence() < min {
+        true => format!("({})", expr_with(s, pi)),
+        false => expr_with(s, pi),
+    }
+}
+
+/// Writes a product, collapsing repeated factors to powers.
+fn product(factors: &[&Sym], pi: &str) -> String {
+    let mut grouped: Vec<(&Sym, usize)> = Vec::new();
+    for factor in factors {
+        match grouped.iter_mut().find(|(s, _)| *s == *factor) {
+            Some((_, n)) => *n += 1,
+            None => grouped.push((factor, 1)),
+        }
+    }
+    grouped
+        .into_iter()
+        .enumerate()
+        .map(|(idx, (s, n))| match (idx, n) {
+            // -a*b is the same as -(a*b)
+            (0, 1) => wrap(s, 2, pi),
+            (_, 1) => wrap(s, 3, pi),
+            (_, n) => format!("{}^{n}", wrap(s, 4, pi)),
+        })
+        .collect::<Vec<String>>()
+        .join("*")
+}
+
+/// A plain `pi` is a double, which would make e.g. `cos(pi/2)` inexact.
+const PI: &str = "sym(pi)";
+
+/// Converts an expression to MATLAB syntax.
+pub fn expr(s: &Sym) -> String {
+    expr_with(s, PI)
+}
+
+/// Converts an expression to MATLAB syntax, the constant pi is written out as the text given in
+/// `pi`, e.g. `sym(pi)` for symbolic MATLAB or `π` for Julia.
+///
+/// The syntax is shared by Octave and Julia, which only differ in how pi is spelled.
+pub(crate) fn expr_with(s: &Sym, pi: &str) -> String {
+    match s {
+        Sym::Number(n) => format!("{n}"),
+        Sym::Identifier(i) => identifier(i),
+        Sym::Constant(Constant::Pi) => pi.to_string(),
+        Sym::Operation(o) => match &**o {
+            Operation::Sqrt(s) => format!("sqrt({})", expr_with(s, pi)),
+            Operation::Sin(s) => format!("sin({})", expr_with(s, pi)),
+            Operation::Cos(s) => format!("cos({})", expr_with(s, pi)),
+            Operation::Nop(s) => expr_with(s, pi),
+            Operation::UnSub(s) => format!("-{}", wrap(s, 3, pi)),
+            Operation::Add(s1, s2) => format!("{} + {}", wrap(s1, 1, pi), wrap(s2, 1, pi)),
+            Operation::Sub(s1, s2) => format!("{} - {}", wrap(s1, 1, pi), wrap(s2, 2, pi)),
+            Operation::Sum(els) => els
+                .iter()
+                .map(|s| wrap(s, 1, pi))
+                .collect::<Vec<String>>()
+                .join(" + "),
+            Operation::Mul(s1, s2) => product(&[s1, s2], pi),
+            Operation::Prod(els) => product(&els.iter().collect::<Vec<&Sym>>(), pi),
+            Operation::Div(s1, s2) => format!("{}/{}", wrap(s1, 3, pi), wrap(s2, 4, pi)),
+            Operation::Rem(s1, s2) => format!("mod({}, {})", expr_with(s1, pi), expr_with(s2, pi)),
+        },
+    }
+}
+
+/// Collects all identifiers in the expression, in order of first occurrence.
+pub fn identifiers(s: &Sym, ret: &mut Vec<&'static str>) {
+    match s {
+        Sym::Identifier(i) if !ret.contains(i) => ret.push(i),
+        Sym::Operation(o) => {
+            for operand in o.operands() {
+                identifiers(operand, ret);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Writes the transform of one DH row, `[theta, d, a, alpha]`, in terms of the row entries. The
+/// constant pi is spelled as in [`expr_with`].
+pub(crate) fn dh_elements(params: &[Sym; 4], convention: Convention, pi: &str) -> [[String; 4]; 4] {
+    let [theta, d, a, alpha] = params;
+    let (theta, alpha) = (expr_with(theta, pi), expr_with(alpha, pi));
+    let (d, a) = (wrap(d, 3, pi), wrap(a, 3, pi));
+    let (ct, st) = (format!("cos({theta})"), format!("sin({theta})"));
+    let (ca, sa) = (format!("cos({alpha})"), format!("sin({alpha})"));
+    match convention {
+        Convention::Standard => [
+            [
+                ct.clone(),
+                format!("-{st}*{ca}"),
+                format!("{st}*{sa}"),
+                format!("{a}*{ct}"),
+            ],
+            [
+                st.clone(),
+                format!("{ct}*{ca}"),
+                format!("-{ct}*{sa}"),
+                format!("{a}*{st}"),
+            ],
+            ["0".to_string(), sa, ca, d],
+            [
+                "0".to_string(),
+                "0".to_string(),
+                "0".to_string(),
+                "1".to_string(),
+            ],
+        ],
+        Convention::Modified => [
+            [ct.clone(), format!("-{st}"), "0".to_string(), a],
+            [
+                format!("{st}*{ca}"),
+                format!("{ct}*{ca}"),
+                format!("-{sa}"),
+                format!("-{sa}*{d}"),
+            ],
+            [
+                format!("{st}*{sa}"),
+                format!("{ct}*{sa}"),
+                ca.clone(),
+                format!("{ca}*{d}"),
+            ],
+            [
+                "0".to_string(),
+                "0".to_string(),
+                "0".to_string(),
+                "1".to_string(),
+            ],
+        ],
+    }
+}
+
+struct Transform {
+    prev: usize,
+    curr: usize,
+    elements: [[String; 4]; 4],
+}
+
+/// Builds a MATLAB script from a chain of transforms.
+///
+/// ```
+/// use robotics::decore::matlab::MatlabScript;
+/// use robotics::pose::Pose;
+/// use robotics::syms::Sym;
+///
+/// let p0_1 = Pose::<Sym, 0, 1>::from_dh(
+///     Sym::Identifier("d_1"),
+///     Sym::Identifier("q_1"),
+///     Sym::Number(0.),
+///     Sym::Number(0.),
+/// )
+/// .unwrap();
+/// let p1_2 = Pose::<Sym, 1, 2>::from_dh(
+///     Sym::Number(0.),
+///     Sym::Identifier("q_2"),
+///     Sym::Identifier("a_2"),
+///     Sym::Number(0.),
+/// )
+/// .unwrap();
+/// let script = MatlabScript::new().pose(&p0_1).pose(&p1_2).to_script();
+/// assert!(script.contains("syms q_1 d_1 q_2 a_2 real"));
+/// assert!(script.contains("T0_2 = simplify(T0_1*T1_2);"));
+/// ```
+#[derive(Default)]
+pub struct MatlabScript {
+    identifiers: Vec<&'static str>,
+    transforms: Vec<Transform>,
+}
+
+impl MatlabScript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends a transform to the chain, it is named `T{PREV}_{CURR}` in the script.
+    pub fn pose<T: CompliantNumerical + Trig + Into<Sym>, const PREV: usize, const CURR: usize>(
+        mut self,
+        pose: &Pose<T, PREV, CURR>,
+    ) -> Self {
+        let m: &Matrix<T, 4, 4> = pose.into();
+        let mut elements: [[String; 4]; 4] = Default::default();
+        for (row, el) in elements.iter_mut().enumerate() {
+            for (col, el) in el.iter_mut().enumerate() {
+                let s: Sym = m[(row, col)].clone().into();
+                identifiers(&s, &mut self.identifiers);
+                *el = expr(&s);
+            }
+        }
+        self.transforms.push(Transform {
+            prev: PREV,
+            curr: CURR,
+            elements,
+        });
+        self
+    }
+
+    /// Appends one transform per row of the table, using the convention of the table.
+    ///
+    /// The transforms are written in terms of the table entries, so MATLAB performs the
+    /// multiplication symbolically.
+    pub fn dh_table(mut self, table: &DHTable) -> Self {
+        let offset = self.transforms.last().map(|t| t.curr).unwrap_or(0);
+        let convention = table.convention();
+        for (idx, params) in table.params().iter().enumerate() {
+            for s in params.iter() {
+                identifiers(s, &mut self.identifiers);
+            }
+            let elements = dh_elements(params, convention, PI);
+            self.transforms.push(Transform {
+                prev: offset + idx,
+                curr: offset + idx + 1,
+                elements,
+            });
+        }
+        self
+    }
+
+    /// Renders the script.
+    pub fn to_script(&self) -> String {
+        let mut ret = "% Forward kinematics, generated by the robotics crate\n".to_string();
+        ret += "clear;\n";
+        if !self.identifiers.is_empty() {
+            let names = self
+                .identifiers
+                .iter()
+                .map(|i| identifier(i))
+                .collect::<Vec<String>>()
+                .join(" ");
+            ret += format!("syms {names} real\n").as_str();
+        }
+        ret += "\n";
+
+        for t in self.transforms.iter() {
+            let rows = t
+                .elements
+                .iter()
+                .map(|row| row.join(", "))
+                .collect::<Vec<String>>()
+                .join(";\n\t");
+            ret += format!("T{}_{} = [\n\t{rows}\n];\n", t.prev, t.curr).as_str();
+        }
+
+        let (first, rest) = match self.transforms.split_first() {
+            Some(el) => el,
+            None => return ret,
+        };
+        let mut composite = format!("T{}_{}", first.prev, first.curr);
+        for t in rest {
+            let next = format!("T{}_{}", first.prev, t.curr);
+            ret += format!("{next} = simplify({composite}*T{}_{});\n", t.prev, t.curr).as_str();
+            composite = next;
+        }
+        ret += format!("fk = {composite}(1:3, 4)\n").as_str();
+        ret
+    }
+}
@@ -0,0 +1,150 @@
+//! Parsing of the TeX style identifiers used throughout the crate, e.g. `\theta_1`, `a_{12}`
+//! or `^0T_3`, for the backends that can not simply pass them through.
+
+const GREEK: [(&str, char); 34] = [
+    ("alpha", 'α'),
+    ("beta", 'β'),
+    ("gamma", 'γ'),
+    ("delta", 'δ'),
+    ("epsilon", 'ϵ'),
+    ("varepsilon", 'ε'),
+    ("zeta", 'ζ'),
+    ("eta", 'η'),
+    ("theta", 'θ'),
+    ("vartheta", 'ϑ'),
+    ("iota", 'ι'),
+    ("kappa", 'κ'),
+    ("lambda", 'λ'),
+    ("mu", 'μ'),
+    ("nu", 'ν'),
+    ("xi", 'ξ'),
+    ("pi", 'π'),
+    ("rho", 'ρ'),
+    ("sigma", 'σ'),
+    ("tau", 'τ'),
+    ("upsilon", 'υ'),
+    ("phi", 'ϕ'),
+    ("varphi", 'φ'),
+    ("chi", 'χ'),
+    ("psi", 'ψ'),
+    ("omega", 'ω'),
+    ("Gamma", 'Γ'),
+    ("Delta", 'Δ'),
+    ("Theta", 'Θ'),
+    ("Lambda", 'Λ'),
+    ("Pi", 'Π'),
+    ("Sigma", 'Σ'),
+    ("Phi", 'Φ'),
+    ("Omega", 'Ω'),
+];
+
+/// Returns the greek letter for a TeX command name, i.e. `theta` gives `θ`.
+pub(crate) fn greek(name: &str) -> Option<char> {
+    GREEK.iter().find(|(n, _)| *n == name).map(|(_, c)| *c)
+}
+
+/// A piece of an identifier, either plain text or a TeX command such as `\theta`.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Part {
+    Text(String),
+    Command(String),
+}
+
+/// An identifier split in to its base and scripts.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub(crate) struct Name {
+    /// Leading superscript, as in `^0T_1`.
+    pub pre: Option<Vec<Part>>,
+    pub base: Vec<Part>,
+    pub sub: Option<Vec<Part>>,
+    pub sup: Option<Vec<Part>>,
+}
+
+fn parts(s: &str) -> Vec<Part> {
+    let mut ret = Vec::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                let mut cmd = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_alphabetic()) {
+                    cmd.push(c);
+                }
+                ret.push(Part::Command(cmd));
+            }
+            '{' | '}' => {}
+            c => match ret.last_mut() {
+                Some(Part::Text(t)) => t.push(c),
+                _ => ret.push(Part::Text(c.to_string())),
+            },
+        }
+    }
+    ret
+}
+
+/// Reads a script argument, either a `{group}`, a `\command` or a single character. Returns
+/// `None` for an unclosed group.
+fn group(chars: &[char], idx: &mut usize) -> Option<String> {
+    let start = *idx;
+    match chars.get(*idx) {
+        Some('{') => {
+            let mut depth = 0;
+            while let Some(c) = chars.get(*idx) {
+                *idx += 1;
+                match c {
+                    '{' => depth += 1,
+                    '}' if depth == 1 => return Some(chars[start + 1..*idx - 1].iter().collect()),
+                    '}' => depth -= 1,
+                    _ => {}
+                }
+            }
+            None
+        }
+        Some('\\') => {
+            *idx += 1;
+            while chars.get(*idx).is_some_and(|c| c.is_alphabetic()) {
+                *idx += 1;
+            }
+            Some(chars[start..*idx].iter().collect())
+        }
+        Some(c) => {
+            *idx += 1;
+            Some(c.to_string())
+        }
+        None => Some(String::new()),
+    }
+}
+
+/// Splits an identifier in to its base and scripts. Malformed identifiers, i.e. with an unclosed
+/// group, are kept as plain text.
+pub(crate) fn parse(identifier: &str) -> Name {
+    split(identifier).unwrap_or_else(|| Name {
+        base: vec![Part::Text(identifier.to_string())],
+        ..Default::default()
+    })
+}
+
+fn split(identifier: &str) -> Option<Name> {
+    let chars: Vec<char> = identifier.chars().collect();
+    let mut idx = 0;
+    let mut ret = Name::default();
+    if chars.first() == Some(&'^') {
+        idx += 1;
+        ret.pre = Some(parts(&group(&chars, &mut idx)?));
+    }
+    let start = idx;
+    while idx < chars.len() && !matches!(chars[idx], '_' | '^') {
+        idx += 1;
+    }
+    ret.base = parts(&chars[start..idx].iter().collect::<String>());
+    while idx < chars.len() {
+        let sub = chars[idx] == '_';
+        idx += 1;
+        let script = Some(parts(&group(&chars, &mut idx)?));
+        match sub {
+            true => ret.sub = script,
+            false => ret.sup = script,
+        }
+    }
+    Some(ret)
+}
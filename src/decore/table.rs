@@ -0,0 +1,312 @@
+//! Rendering of DH tables to documents other than equations.
+//!
+//! The same table can be written as TeX for reports, Markdown for READMEs, CSV for spreadsheets,
+//! HTML or aligned plain text for terminals, see [`DHTable::render`](crate::link::DHTable::render).
+use crate::link::Convention;
+use crate::syms::Sym;
+
+use super::decorators::ToTex;
+use super::matlab;
+
+/// The output format of a rendered table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableFormat {
+    Tex,
+    Markdown,
+    Csv,
+    Html,
+    Plain,
+}
+
+/// Options for rendering a table.
+///
+/// Cells that depend on a joint variable of the table are highlighted unless
+/// [`highlight`](Self::highlight) is given.
+///
+/// ```
+/// use robotics::decore::table::{TableFormat, TableOptions};
+/// use robotics::link::DHBuilder;
+/// use robotics::syms::Sym;
+///
+/// let table = DHBuilder::new()
+///     .theta(Sym::Identifier("q_1"))
+///     .d(Sym::Number(0.5))
+///     .a(Sym::Number(1.))
+///     .alpha(Sym::Number(0.))
+///     .to_table();
+/// let options = TableOptions::new()
+///     .caption("DH parameters of the arm")
+///     .label("table:arm")
+///     .precision(3);
+/// let rendered = table.render(TableFormat::Markdown, &options);
+/// assert!(rendered.contains("**`q_1`**"));
+///
+/// let plain = table.render(TableFormat::Markdown, &options.highlight(&[]));
+/// assert!(!plain.contains("**`q_1`**"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TableOptions {
+    caption: Option<String>,
+    label: Option<String>,
+    headers: Option<[String; 5]>,
+    highlight: Option<Vec<&'static str>>,
+    precision: Option<usize>,
+}
+
+impl TableOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+    /// Sets the caption, it is ignored by the CSV backend.
+    pub fn caption(mut self, caption: impl Into<String>) -> Self {
+        self.caption = Some(caption.into());
+        self
+    }
+    /// Sets the label used to reference the table, it is ignored by the Markdown, CSV and plain
+    /// text backends.
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+    /// Replaces the `j, θ, d, a, α` column headers, the headers are written verbatim.
+    ///
+    /// Tables in the modified convention are ordered `j, α, a, θ, d`.
+    pub fn headers(mut self, headers: [&str; 5]) -> Self {
+        self.headers = Some(headers.map(|h| h.to_string()));
+        self
+    }
+    /// Highlights every cell that depends on one of the given variables instead of the joint
+    /// variables of the table, an empty list disables highlighting.
+    pub fn highlight(mut self, variables: &[&'static str]) -> Self {
+        self.highlight = Some(variables.to_vec());
+        self
+    }
+    /// Rounds numbers to at most `digits` decimals.
+    pub fn precision(mut self, digits: usize) -> Self {
+        self.precision = Some(digits);
+        self
+    }
+}
+
+fn round(s: &Sym, digits: usize) -> Sym {
+    let scale = 10f32.powi(digits as i32);
+    let mut s = s.clone();
+    match &mut s {
+        Sym::Number(n) => *n = (*n * scale).round() / scale + 0.,
+        Sym::Operation(o) => {
+            for operand in o.operands_mut() {
+                *operand = round(operand, digits);
+            }
+        }
+        _ => {}
+    }
+    s
+}
+
+fn highlighted(s: &Sym, variables: &[&'static str]) -> bool {
+    let mut identifiers = Vec::new();
+    matlab::identifiers(s, &mut identifiers);
+    identifiers.iter().any(|i| variables.contains(i))
+}
+
+fn escape_html(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn escape_csv(s: &str) -> String {
+    match s.contains([',', '"', '\n']) {
+        true => format!("\"{}\"", s.replace('"', "\"\"")),
+        false => s.to_string(),
+    }
+}
+
+fn cell(
+    s: &Sym,
+    format: TableFormat,
+    options: &TableOptions,
+    variables: &[&'static str],
+) -> String {
+    let s = match options.precision {
+        Some(digits) => round(s, digits),
+        None => s.clone(),
+    };
+    let text = match format {
+        TableFormat::Tex => s.to_tex(None),
+        TableFormat::Csv => matlab::expr_with(&s, "pi"),
+        _ => matlab::expr_with(&s, "π"),
+    };
+    let highlight = highlighted(&s, variables);
+    match (format, highlight) {
+        (TableFormat::Tex, true) => format!("$\\boldsymbol{{{text}}}$"),
+        (TableFormat::Tex, false) => format!("${text}$"),
+        (TableFormat::Markdown, true) => format!("**`{text}`**"),
+        (TableFormat::Markdown, false) => format!("`{text}`"),
+        (TableFormat::Csv, _) => escape_csv(&text),
+        (TableFormat::Html, true) => format!("<strong>{}</strong>", escape_html(&text)),
+        (TableFormat::Html, false) => escape_html(&text),
+        (TableFormat::Plain, true) => format!("[{text}]"),
+        (TableFormat::Plain, false) => text,
+    }
+}
+
+fn headers(convention: Convention, format: TableFormat, options: &TableOptions) -> [String; 5] {
+    if let Some(headers) = &options.headers {
+        return headers.clone();
+    }
+    match (convention, format) {
+        (Convention::Standard, TableFormat::Tex) => ["$j$", "$\\theta$", "$d$", "$a$", "$\\alpha$"],
+        (Convention::Standard, TableFormat::Csv) => ["j", "theta", "d", "a", "alpha"],
+        (Convention::Standard, _) => ["j", "θ", "d", "a", "α"],
+        (Convention::Modified, TableFormat::Tex) => [
+            "$j$",
+            "$\\alpha_{j-1}$",
+            "$a_{j-1}$",
+            "$\\theta_j$",
+            "$d_j$",
+        ],
+        (Convention::Modified, TableFormat::Csv) => {
+            ["j", "alpha(j-1)", "a(j-1)", "theta(j)", "d(j)"]
+        }
+        (Convention::Modified, _) => ["j", "α(j-1)", "a(j-1)", "θ(j)", "d(j)"],
+    }
+    .map(|h| h.to_string())
+}
+
+/// Renders the `[theta, d, a, alpha]` rows of a table, highlighting the given joint variables
+/// unless the options override them.
+pub(crate) fn render(
+    rows: &[[Sym; 4]],
+    convention: Convention,
+    variables: &[&'static str],
+    format: TableFormat,
+    options: &TableOptions,
+) -> String {
+    let headers = headers(convention, format, options);
+    let variables = options.highlight.as_deref().unwrap_or(variables);
+    let cells: Vec<[String; 5]> = rows
+        .iter()
+        .enumerate()
+        .map(|(idx, row)| {
+            let index = match format {
+                TableFormat::Tex => format!("${}$", idx + 1),
+                _ => format!("{}", idx + 1),
+            };
+            let [theta, d, a, alpha] = row.each_ref().map(|s| cell(s, format, options, variables));
+            match convention {
+                Convention::Standard => [index, theta, d, a, alpha],
+                Convention::Modified => [index, alpha, a, theta, d],
+            }
+        })
+        .collect();
+    match format {
+        TableFormat::Tex => tex(&headers, &cells, options),
+        TableFormat::Markdown => markdown(&headers, &cells, options),
+        TableFormat::Csv => csv(&headers, &cells),
+        TableFormat::Html => html(&headers, &cells, options),
+        TableFormat::Plain => plain(&headers, &cells, options),
+    }
+}
+
+fn tex(headers: &[String; 5], cells: &[[String; 5]], options: &TableOptions) -> String {
+    let mut ret = "\\begin{table}[H]".to_string();
+    if let Some(label) = &options.label {
+        ret += format!("\\label{{{label}}}").as_str();
+    }
+    ret += "\\centering\n\t\\begin{tabular}{|c|c|c|c|c|}\\hline\n";
+    ret += format!("\t\t{}\t\\\\\\hline\n", headers.join("\t&\t")).as_str();
+    for row in cells {
+        ret += format!("\t\t{}\\\\\n", row.join("\t&\t")).as_str();
+    }
+    ret += "\t\\hline\n\t\\end{tabular}\n";
+    if let Some(caption) = &options.caption {
+        ret += format!("\t\\caption{{{caption}}}\n").as_str();
+    }
+    ret += "\\end{table}";
+    ret
+}
+
+fn markdown(headers: &[String; 5], cells: &[[String; 5]], options: &TableOptions) -> String {
+    let mut ret = String::new();
+    if let Some(caption) = &options.caption {
+        ret += format!("**{caption}**\n\n").as_str();
+    }
+    let row = |cells: &[String]| format!("| {} |\n", cells.join(" | "));
+    ret += row(headers).as_str();
+    ret += "|---|---|---|---|---|\n";
+    for cells in cells {
+        ret += row(cells).as_str();
+    }
+    ret
+}
+
+fn csv(headers: &[String; 5], cells: &[[String; 5]]) -> String {
+    let mut ret = headers.each_ref().map(|h| escape_csv(h)).join(",") + "\n";
+    for row in cells {
+        ret += row.join(",").as_str();
+        ret += "\n";
+    }
+    ret
+}
+
+fn html(headers: &[String; 5], cells: &[[String; 5]], options: &TableOptions) -> String {
+    let mut ret = match &options.label {
+        Some(label) => format!("<table id=\"{}\">\n", escape_html(label)),
+        None => "<table>\n".to_string(),
+    };
+    if let Some(caption) = &options.caption {
+        ret += format!("\t<caption>{}</caption>\n", escape_html(caption)).as_str();
+    }
+    ret += "\t<thead>\n\t\t<tr>";
+    for header in headers {
+        ret += format!("<th>{}</th>", escape_html(header)).as_str();
+    }
+    ret += "</tr>\n\t</thead>\n\t<tbody>\n";
+    for row in cells {
+        ret += "\t\t<tr>";
+        for cell in row {
+            ret += format!("<td>{cell}</td>").as_str();
+        }
+        ret += "</tr>\n";
+    }
+    ret += "\t</tbody>\n</table>";
+    ret
+}
+
+fn plain(headers: &[String; 5], cells: &[[String; 5]], options: &TableOptions) -> String {
+    let mut widths = headers.each_ref().map(|h| h.chars().count());
+    for row in cells {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+    let row = |cells: &[String; 5]| {
+        let padded = cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .collect::<Vec<String>>();
+        format!("{}\n", padded.join("  ").trim_end())
+    };
+
+    let mut ret = String::new();
+    if let Some(caption) = &options.caption {
+        ret += format!("{caption}\n").as_str();
+    }
+    ret += row(headers).as_str();
+    ret += format!(
+        "{}\n",
+        widths
+            .iter()
+            .map(|width| "-".repeat(*width))
+            .collect::<Vec<String>>()
+            .join("  ")
+    )
+    .as_str();
+    for cells in cells {
+        ret += row(cells).as_str();
+    }
+    ret
+}
@@ -0,0 +1,106 @@
+//! [`ToTypst`] for scalars.
+use crate::syms::{Constant, Operation, Sym};
+
+use super::decorators::ToTypst;
+use super::names::{self, Part};
+
+/// Typst reads multi letter words in math mode as variables, so anything but single letters,
+/// numbers and greek letters is quoted.
+fn parts(parts: &[Part]) -> String {
+    match parts {
+        [Part::Command(c)] if names::greek(c).is_some() => c.clone(),
+        _ => {
+            let text = parts
+                .iter()
+                .map(|p| match p {
+                    Part::Text(t) => t.clone(),
+                    Part::Command(c) => names::greek(c).map(String::from).unwrap_or(c.clone()),
+                })
+                .collect::<String>();
+            match text.chars().count() == 1 || text.chars().all(|c| c.is_ascii_digit()) {
+                true => text,
+                false => format!("\"{text}\""),
+            }
+        }
+    }
+}
+
+/// Renders a TeX style identifier such as `\theta_1` or `^0T_1`.
+pub(crate) fn identifier(identifier: &str) -> String {
+    let name = names::parse(identifier);
+    let base = parts(&name.base);
+    let script = |p: &Option<Vec<Part>>| p.as_ref().map(|p| parts(p));
+    match (script(&name.pre), script(&name.sub), script(&name.sup)) {
+        (Some(pre), sub, sup) => {
+            let mut ret = format!("attach({base}, tl: {pre}");
+            if let Some(sub) = sub {
+                ret += format!(", br: {sub}").as_str();
+            }
+            if let Some(sup) = sup {
+                ret += format!(", tr: {sup}").as_str();
+            }
+            ret + ")"
+        }
+        (None, sub, sup) => {
+            let mut ret = base;
+            if let Some(sub) = sub {
+                ret += format!("_({sub})").as_str();
+            }
+            if let Some(sup) = sup {
+                ret += format!("^({sup})").as_str();
+            }
+            ret
+        }
+    }
+}
+
+fn wrap(s: &Sym, min: u8) -> String {
+    match s.precedence() < min {
+        true => format!("({})", expr(s)),
+        false => expr(s),
+    }
+}
+
+fn join(els: &[&Sym], min: u8, op: &str) -> String {
+    els.iter()
+        .map(|s| wrap(s, min))
+        .collect::<Vec<String>>()
+        .join(op)
+}
+
+fn expr(s: &Sym) -> String {
+    match s {
+        Sym::Number(n) => format!("{n}"),
+        Sym::Identifier(i) => identifier(i),
+        Sym::Constant(Constant::Pi) => "pi".to_string(),
+        Sym::Operation(o) => match &**o {
+            Operation::Sqrt(s) => format!("sqrt({})", expr(s)),
+            Operation::Sin(s) => format!("sin({})", expr(s)),
+            Operation::Cos(s) => format!("cos({})", expr(s)),
+            Operation::Nop(s) => expr(s),
+            Operation::UnSub(s) => format!("-{}", wrap(s, 3)),
+            Operation::Add(s1, s2) => join(&[s1, s2], 1, " + "),
+            Operation::Sum(els) => join(&els.iter().collect::<Vec<&Sym>>(), 1, " + "),
+            Operation::Sub(s1, s2) => format!("{} - {}", wrap(s1, 1), wrap(s2, 2)),
+            Operation::Mul(s1, s2) => join(&[s1, s2], 3, " dot "),
+            Operation::Prod(els) => join(&els.iter().collect::<Vec<&Sym>>(), 3, " dot "),
+            Operation::Div(s1, s2) => format!("frac({}, {})", expr(s1), expr(s2)),
+            Operation::Rem(s1, s2) => format!("{} mod {}", wrap(s1, 3), wrap(s2, 4)),
+        },
+    }
+}
+
+impl ToTypst for Sym {
+    fn to_typst_internal(&self, identifier: Option<&'static str>) -> String {
+        match identifier {
+            Some(id) => format!("{} = {}", self::identifier(id), expr(self)),
+            None => expr(self),
+        }
+    }
+}
+
+impl ToTypst for f32 {
+    fn to_typst_internal(&self, identifier: Option<&'static str>) -> String {
+        Sym::Number(*self).to_typst_internal(identifier)
+    }
+}
@@ -0,0 +1,151 @@
+//! Unit dual quaternions for representing rigid transforms.
+//!
+//! A dual quaternion `r + e d` holds the rotation in the real part `r` and the translation in the
+//! dual part `d = 1/2 t r`. Like [`Pose`] it is typed by the frames it maps between, so
+//! `^0D_1 * ^1D_2` gives `^0D_2`.
+//!
+//! ```
+//! use matrs::predule::*;
+//! use robotics::dual_quaternion::DualQuaternion;
+//! use robotics::pose::Pose;
+//!
+//! let pose_a = Pose::<f32, 0, 1>::from_translation(Vector::new_from_data([0., 0., 0.]));
+//! let pose_b = Pose::<f32, 0, 1>::from_translation(Vector::new_from_data([2., 0., 0.]));
+//! let a: DualQuaternion<f32, 0, 1> = (&pose_a).into();
+//! let b: DualQuaternion<f32, 0, 1> = (&pose_b).into();
+//! let blended: Pose<f32, 0, 1> = a.sclerp(&b, 0.5).into();
+//! assert!((blended.fk()[0] - 1.).abs() < 1e-6);
+//! ```
+use std::ops::Mul;
+
+use matrs::{matrix::helpers::rotations::Trig, CompliantNumerical, MatrixInterface, VectorTrait};
+use num_traits::Float;
+
+use crate::pose::Pose;
+use crate::quaternion::Quaternion;
+use crate::{Matrix, Vector};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DualQuaternion<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize> {
+    pub real: Quaternion<T>,
+    pub dual: Quaternion<T>,
+}
+
+fn add<T: CompliantNumerical + Trig>(a: Quaternion<T>, b: Quaternion<T>) -> Quaternion<T> {
+    Quaternion::new(a.w + b.w, a.x + b.x, a.y + b.y, a.z + b.z)
+}
+
+fn scale<T: CompliantNumerical + Trig>(q: Quaternion<T>, s: T) -> Quaternion<T> {
+    Quaternion::new(q.w * s.clone(), q.x * s.clone(), q.y * s.clone(), q.z * s)
+}
+
+impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize>
+    DualQuaternion<T, PREV, CURR>
+{
+    /// Creates a dual quaternion from its parts, it is not normalized.
+    pub fn new(real: Quaternion<T>, dual: Quaternion<T>) -> Self {
+        Self { real, dual }
+    }
+    pub fn identity() -> Self {
+        Self::new(
+            Quaternion::identity(),
+            Quaternion::new(T::zero(), T::zero(), T::zero(), T::zero()),
+        )
+    }
+    /// Creates the transform that first rotates by `rotation` and then translates by
+    /// `translation`, the rotation is assumed to be of unit length.
+    pub fn from_parts(rotation: Quaternion<T>, translation: Vector<T, 3>) -> Self {
+        let half = T::one() / (T::one() + T::one());
+        let t = Quaternion::new(
+            T::zero(),
+            translation[0].clone(),
+            translation[1].clone(),
+            translation[2].clone(),
+        );
+        let dual = scale(t * rotation.clone(), half);
+        Self::new(rotation, dual)
+    }
+    pub fn rotation(&self) -> Quaternion<T> {
+        self.real.clone()
+    }
+    /// Returns the translation, `2 d r*`.
+    pub fn translation(&self) -> Vector<T, 3> {
+        let t = scale(
+            self.dual.clone() * self.real.conjugate(),
+            T::one() + T::one(),
+        );
+        Vector::new_from_data([t.x, t.y, t.z])
+    }
+    /// Returns the inverse transform, for a unit dual quaternion this is `r* + e d*`.
+    pub fn inverse(&self) -> DualQuaternion<T, CURR, PREV> {
+        DualQuaternion::new(self.real.conjugate(), self.dual.conjugate())
+    }
+    /// Scales the dual quaternion to unit length and removes the part of `d` along `r`, so that it
+    /// represents a rigid transform again after accumulating rounding errors.
+    pub fn normalize(&self) -> Self {
+        let norm = self.real.norm();
+        let real = scale(self.real.clone(), T::one() / norm.clone());
+        let dual = scale(self.dual.clone(), T::one() / norm);
+        let along = real.dot(&dual);
+        let dual = add(dual, scale(real.clone(), T::zero() - along));
+        Self::new(real, dual)
+    }
+}
+
+impl<T: CompliantNumerical + Trig + Float, const PREV: usize, const CURR: usize>
+    DualQuaternion<T, PREV, CURR>
+{
+    /// Screw linear interpolation, `t = 0` gives `self` and `t = 1` gives `other`.
+    ///
+    /// Moves with constant speed along the screw motion between the two transforms, taking the
+    /// shortest path for the rotation.
+    pub fn sclerp(&self, other: &Self, t: T) -> Self {
+        let mut other = other.clone();
+        if self.real.dot(&other.real) < T::zero() {
+            other = Self::new(scale(other.real, -T::one()), scale(other.dual, -T::one()));
+        }
+        let relative: Pose<T, CURR, CURR> = (self.inverse() * other).into();
+        let step: Pose<T, CURR, CURR> = relative.log().scale(t).exp();
+        (self.clone() * DualQuaternion::from(&step)).normalize()
+    }
+}
+
+/// Composes the transforms, `a * b` applies `b` first and then `a`.
+impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize, const OTHER: usize>
+    Mul<DualQuaternion<T, CURR, OTHER>> for DualQuaternion<T, PREV, CURR>
+{
+    type Output = DualQuaternion<T, PREV, OTHER>;
+    fn mul(self, rhs: DualQuaternion<T, CURR, OTHER>) -> Self::Output {
+        let real = self.real.clone() * rhs.real.clone();
+        let dual = add(self.real * rhs.dual, self.dual * rhs.real);
+        DualQuaternion::new(real, dual)
+    }
+}
+
+impl<T: CompliantNumerical + Trig + PartialOrd, const PREV: usize, const CURR: usize>
+    From<&Pose<T, PREV, CURR>> for DualQuaternion<T, PREV, CURR>
+{
+    fn from(value: &Pose<T, PREV, CURR>) -> Self {
+        let m: &Matrix<T, 4, 4> = value.into();
+        let t = Vector::new_from_data([m[(0, 3)].clone(), m[(1, 3)].clone(), m[(2, 3)].clone()]);
+        Self::from_parts(value.into(), t)
+    }
+}
+
+impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize>
+    From<DualQuaternion<T, PREV, CURR>> for Pose<T, PREV, CURR>
+{
+    fn from(value: DualQuaternion<T, PREV, CURR>) -> Self {
+        let rot = value.real.to_matrix();
+        let t = value.translation();
+        let mut rpr = <Matrix<T, 4, 4>>::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                rpr[(i, j)] = rot[(i, j)].clone();
+            }
+            rpr[(i, 3)] = t[i].clone();
+        }
+        rpr[(3, 3)] = T::one();
+        Pose::from_matrix(rpr)
+    }
+}
@@ -0,0 +1,300 @@
+//! Euler and Tait-Bryan angles.
+//!
+//! A [`EulerOrder`] names the axes in the order the rotations are applied. Intrinsic rotations
+//! are about the axes of the rotating frame, `R = R_1(a) R_2(b) R_3(c)`, while extrinsic rotations
+//! are about the fixed axes, `R = R_3(c) R_2(b) R_1(a)`.
+//!
+//! ```
+//! use robotics::euler::{EulerOrder, Rotation};
+//! use robotics::pose::Pose;
+//!
+//! let wrist =
+//!     Pose::<f32, 3, 6>::from_euler([0.3, 0.8, -1.2], EulerOrder::ZYZ, Rotation::Intrinsic)
+//!         .unwrap();
+//! let [a, b, c] = wrist.euler_angles(EulerOrder::ZYZ, Rotation::Intrinsic);
+//! assert!((a - 0.3).abs() < 1e-5 && (b - 0.8).abs() < 1e-5 && (c + 1.2).abs() < 1e-5);
+//! ```
+use matrs::matrix::helpers::rotations::{rotx, roty, rotz, Error, Trig};
+use matrs::{CompliantNumerical, MatrixInterface};
+use num_traits::Float;
+
+use crate::pose::Pose;
+use crate::Matrix;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Axis {
+    X,
+    Y,
+    Z,
+}
+
+impl Axis {
+    fn index(self) -> usize {
+        match self {
+            Self::X => 0,
+            Self::Y => 1,
+            Self::Z => 2,
+        }
+    }
+    /// Returns the rotation of `angle` about the axis.
+    pub fn rotation<T: CompliantNumerical + Trig>(
+        self,
+        angle: T,
+    ) -> Result<Matrix<T, 3, 3>, Error> {
+        match self {
+            Self::X => rotx(angle),
+            Self::Y => roty(angle),
+            Self::Z => rotz(angle),
+        }
+    }
+}
+
+/// The six Tait-Bryan sequences, with three distinct axes, followed by the six proper Euler
+/// sequences, where the first and last axes are the same.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EulerOrder {
+    XYZ,
+    XZY,
+    YXZ,
+    YZX,
+    ZXY,
+    ZYX,
+    XYX,
+    XZX,
+    YXY,
+    YZY,
+    ZXZ,
+    ZYZ,
+}
+
+/// Whether the rotations are about the rotating or the fixed axes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Rotation {
+    Intrinsic,
+    Extrinsic,
+}
+
+impl EulerOrder {
+    pub fn axes(self) -> [Axis; 3] {
+        use Axis::*;
+        match self {
+            Self::XYZ => [X, Y, Z],
+            Self::XZY => [X, Z, Y],
+            Self::YXZ => [Y, X, Z],
+            Self::YZX => [Y, Z, X],
+            Self::ZXY => [Z, X, Y],
+            Self::ZYX => [Z, Y, X],
+            Self::XYX => [X, Y, X],
+            Self::XZX => [X, Z, X],
+            Self::YXY => [Y, X, Y],
+            Self::YZY => [Y, Z, Y],
+            Self::ZXZ => [Z, X, Z],
+            Self::ZYZ => [Z, Y, Z],
+        }
+    }
+    /// Returns the order with the axes reversed, extrinsic rotations in one order are intrinsic
+    /// rotations in the reversed order.
+    pub fn reversed(self) -> Self {
+        match self {
+            Self::XYZ => Self::ZYX,
+            Self::XZY => Self::YZX,
+            Self::YXZ => Self::ZXY,
+            Self::YZX => Self::XZY,
+            Self::ZXY => Self::YXZ,
+            Self::ZYX => Self::XYZ,
+            other => other,
+        }
+    }
+}
+
+/// Composes the rotation matrix for the angles, given in the order of the axes.
+pub fn to_matrix<T: CompliantNumerical + Trig>(
+    angles: [T; 3],
+    order: EulerOrder,
+    rotation: Rotation,
+) -> Result<Matrix<T, 3, 3>, Error> {
+    let [a, b, c] = order.axes();
+    let [ra, rb, rc] = [
+        a.rotation(angles[0].clone())?,
+        b.rotation(angles[1].clone())?,
+        c.rotation(angles[2].clone())?,
+    ];
+    Ok(match rotation {
+        Rotation::Intrinsic => ra * rb * rc,
+        Rotation::Extrinsic => rc * rb * ra,
+    })
+}
+
+/// Extracts the angles, in the order of the axes, from a rotation matrix.
+///
+/// The middle angle is in `[-pi/2, pi/2]` for Tait-Bryan and `[0, pi]` for proper Euler orders.
+/// In gimbal lock only the sum or difference of the first and last angle is defined, the last
+/// angle is then set to zero.
+pub fn from_matrix<T: CompliantNumerical + Float>(
+    m: &Matrix<T, 3, 3>,
+    order: EulerOrder,
+    rotation: Rotation,
+) -> [T; 3] {
+    if rotation == Rotation::Extrinsic {
+        let [c, b, a] = from_matrix(m, order.reversed(), Rotation::Intrinsic);
+        return [a, b, c];
+    }
+    let [i, j, k] = order.axes().map(Axis::index);
+    let r = |row: usize, col: usize| m[(row, col)];
+    // The remaining axis, when the first and last are the same
+    let l = 3 - i - j;
+    let cyclic = |a: usize, b: usize| (a + 1) % 3 == b;
+    let locked = T::one() - T::from(1e-6).unwrap();
+    let clamp = |x: T| x.max(-T::one()).min(T::one());
+
+    if i != k {
+        let s = if cyclic(i, j) { T::one() } else { -T::one() };
+        let sin_b = clamp(s * r(i, k));
+        let b = sin_b.asin();
+        if sin_b.abs() > locked {
+            return [(s * r(k, j)).atan2(r(j, j)), b, T::zero()];
+        }
+        [
+            (-s * r(j, k)).atan2(r(k, k)),
+            b,
+            (-s * r(i, j)).atan2(r(i, i)),
+        ]
+    } else {
+        let s = if cyclic(i, j) { T::one() } else { -T::one() };
+        let cos_b = clamp(r(i, i));
+        let b = cos_b.acos();
+        if cos_b.abs() > locked {
+            return [(s * r(l, j)).atan2(r(j, j)), b, T::zero()];
+        }
+        [r(j, i).atan2(-s * r(l, i)), b, r(i, j).atan2(s * r(i, l))]
+    }
+}
+
+impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize> Pose<T, PREV, CURR> {
+    /// Creates a pure rotation from Euler angles, given in the order of the axes.
+    pub fn from_euler(
+        angles: [T; 3],
+        order: EulerOrder,
+        rotation: Rotation,
+    ) -> Result<Self, Error> {
+        Ok(Self::rot(to_matrix(angles, order, rotation)?))
+    }
+}
+
+impl<T: CompliantNumerical + Trig + Float, const PREV: usize, const CURR: usize>
+    Pose<T, PREV, CURR>
+{
+    /// Extracts the Euler angles of the rotation, see [`from_matrix`].
+    pub fn euler_angles(&self, order: EulerOrder, rotation: Rotation) -> [T; 3] {
+        let m: &Matrix<T, 4, 4> = self.into();
+        let mut rot = <Matrix<T, 3, 3>>::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                rot[(i, j)] = m[(i, j)];
+            }
+        }
+        from_matrix(&rot, order, rotation)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ORDERS: [EulerOrder; 12] = [
+        EulerOrder::XYZ,
+        EulerOrder::XZY,
+        EulerOrder::YXZ,
+        EulerOrder::YZX,
+        EulerOrder::ZXY,
+        EulerOrder::ZYX,
+        EulerOrder::XYX,
+        EulerOrder::XZX,
+        EulerOrder::YXY,
+        EulerOrder::YZY,
+        EulerOrder::ZXZ,
+        EulerOrder::ZYZ,
+    ];
+    const ROTATIONS: [Rotation; 2] = [Rotation::Intrinsic, Rotation::Extrinsic];
+
+    fn tait_bryan(order: EulerOrder) -> bool {
+        let [a, _, c] = order.axes();
+        a != c
+    }
+
+    fn assert_close(lhs: &Matrix<f64, 3, 3>, rhs: &Matrix<f64, 3, 3>) {
+        for i in 0..3 {
+            for j in 0..3 {
+                assert!(
+                    (lhs[(i, j)] - rhs[(i, j)]).abs() < 1e-9,
+                    "element ({i}, {j}) differs"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn round_trip() {
+        for order in ORDERS {
+            // The middle angle within the range returned by `from_matrix`
+            let b = if tait_bryan(order) { -0.6 } else { 1.1 };
+            let angles = [0.4, b, -2.3];
+            for rotation in ROTATIONS {
+                let m = to_matrix(angles, order, rotation).unwrap();
+                let ret = from_matrix(&m, order, rotation);
+                for (ret, angle) in ret.iter().zip(angles) {
+                    assert!(
+                        (ret - angle).abs() < 1e-9,
+                        "{order:?} {rotation:?}: {ret:?}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn extrinsic_is_reversed_intrinsic() {
+        for order in ORDERS {
+            let [a, b, c] = [0.2, -0.5, 0.9];
+            let extrinsic = to_matrix([a, b, c], order, Rotation::Extrinsic).unwrap();
+            let intrinsic = to_matrix([c, b, a], order.reversed(), Rotation::Intrinsic).unwrap();
+            assert_close(&extrinsic, &intrinsic);
+        }
+    }
+
+    #[test]
+    fn gimbal_lock() {
+        let half_pi = std::f64::consts::FRAC_PI_2;
+        for order in ORDERS {
+            let locked: &[f64] = match tait_bryan(order) {
+                true => &[half_pi, -half_pi],
+                false => &[0., std::f64::consts::PI],
+            };
+            for &b in locked {
+                for rotation in ROTATIONS {
+                    let m = to_matrix([0.7, b, 0.4], order, rotation).unwrap();
+                    let ret = from_matrix(&m, order, rotation);
+                    assert!(ret.iter().all(|angle| angle.is_finite()));
+                    // Only the sum or difference is defined, the free angle is set to zero
+                    let free = match rotation {
+                        Rotation::Intrinsic => ret[2],
+                        Rotation::Extrinsic => ret[0],
+                    };
+                    assert_eq!(free, 0., "{order:?} {rotation:?}");
+                    assert_close(&to_matrix(ret, order, rotation).unwrap(), &m);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn pose_round_trip() {
+        let angles = [-1.4, 0.3, 2.9];
+        let pose =
+            Pose::<f64, 0, 1>::from_euler(angles, EulerOrder::ZYX, Rotation::Intrinsic).unwrap();
+        let ret = pose.euler_angles(EulerOrder::ZYX, Rotation::Intrinsic);
+        for (ret, angle) in ret.iter().zip(angles) {
+            assert!((ret - angle).abs() < 1e-9);
+        }
+    }
+}
@@ -0,0 +1,13 @@
+//! Frames identified by name at runtime.
+//!
+//! [`Pose`](crate::pose::Pose) identifies its frames by `usize` const generics, which suits DH
+//! chains where frame `i` follows link `i`. Frames that are better known by name, such as a
+//! world, a camera and a table, are kept in a [`FrameGraph`](graph::FrameGraph), or in a
+//! [`TransformBuffer`](buffer::TransformBuffer) when their poses change over time.
+//!
+//! Named frames are not part of the pose type, i.e. there is no `Pose<T, World, Camera>`. Marker
+//! types as frame parameters would replace the const generics throughout the crate and break the
+//! numeric form existing chains are written in.
+
+pub mod buffer;
+pub mod graph;
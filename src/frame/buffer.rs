@@ -0,0 +1,254 @@
+//! A tree of frames whose poses are sampled over time.
+//!
+//! Like a [`FrameGraph`](super::graph::FrameGraph), but every frame keeps a history of time stamped
+//! poses in its parent, so that transforms can be looked up at any time covered by the samples.
+//! Between samples the rotation is interpolated with SLERP and the translation linearly.
+//!
+//! ```
+//! use matrs::predule::*;
+//! use robotics::frame::buffer::TransformBuffer;
+//! use robotics::frame::graph::FrameError;
+//! use robotics::pose::{Point, Pose};
+//!
+//! let at = |x, y, z| Pose::<f64, 0, 1>::from_translation(Vector::new_from_data([x, y, z]));
+//! let mut buffer = TransformBuffer::new(100).extrapolation(0.005);
+//! buffer.add_root("world")?;
+//! buffer.add_static("robot", "world", &at(0., 0., 1.))?;
+//! buffer.add_frame("tool", "robot")?;
+//! buffer.add_frame("marker", "world")?;
+//! buffer.insert("tool", 0.00, &at(0., 0., 0.))?;
+//! buffer.insert("tool", 0.01, &at(1., 0., 0.))?;
+//! buffer.insert("marker", 0.004, &at(0., 0., 1.))?;
+//! let marker_in_tool: Pose<f64, 1, 2> = buffer.lookup("tool", "marker", 0.004)?;
+//! let marker: Vector<f64, 3> = (&marker_in_tool * Point::origin()).into();
+//! assert!((marker[0] + 0.4).abs() < 1e-9 && marker[2].abs() < 1e-9);
+//! assert!(buffer.lookup::<1, 2>("tool", "marker", 0.02).is_err());
+//! # Ok::<(), FrameError>(())
+//! ```
+use std::collections::VecDeque;
+
+use matrs::{matrix::helpers::rotations::Trig, CompliantNumerical};
+use num_traits::Float;
+
+use crate::pose::Pose;
+
+use super::graph::{erase, identity, FrameError, Tree};
+
+#[derive(Clone, Debug)]
+struct Samples<T: CompliantNumerical + Trig> {
+    /// The poses of the frame in its parent, sorted by time.
+    samples: VecDeque<(f64, Pose<T, 0, 0>)>,
+    /// Static frames use their latest sample at every time.
+    fixed: bool,
+}
+
+/// A [`FrameGraph`](super::graph::FrameGraph) whose edges are histories of poses.
+#[derive(Clone, Debug)]
+pub struct TransformBuffer<T: CompliantNumerical + Trig> {
+    tree: Tree<Samples<T>>,
+    history: usize,
+    extrapolation: f64,
+}
+
+impl<T: CompliantNumerical + Trig + Float> TransformBuffer<T> {
+    /// Creates an empty buffer keeping at most `history` samples per frame, at least one sample
+    /// is always kept.
+    pub fn new(history: usize) -> Self {
+        Self {
+            tree: Tree::new(),
+            history: history.max(1),
+            extrapolation: 0.,
+        }
+    }
+    /// Allows lookups up to `limit` outside the samples of a frame, the nearest sample is used
+    /// there. By default any lookup outside the samples is an error.
+    pub fn extrapolation(mut self, limit: f64) -> Self {
+        self.extrapolation = limit;
+        self
+    }
+    /// Adds a frame without a parent, the root of a new tree.
+    pub fn add_root(&mut self, name: impl Into<String>) -> Result<(), FrameError> {
+        let samples = Samples {
+            samples: VecDeque::from([(0., identity())]),
+            fixed: true,
+        };
+        self.tree.insert(name.into(), None, samples)
+    }
+    /// Adds a moving frame below `parent`, its poses are added with [`TransformBuffer::insert`].
+    pub fn add_frame(&mut self, name: impl Into<String>, parent: &str) -> Result<(), FrameError> {
+        let samples = Samples {
+            samples: VecDeque::new(),
+            fixed: false,
+        };
+        self.tree.insert(name.into(), Some(parent), samples)
+    }
+    /// Adds a frame below `parent` whose pose does not change over time.
+    pub fn add_static<const PREV: usize, const CURR: usize>(
+        &mut self,
+        name: impl Into<String>,
+        parent: &str,
+        pose: &Pose<T, PREV, CURR>,
+    ) -> Result<(), FrameError> {
+        let samples = Samples {
+            samples: VecDeque::from([(0., erase(pose))]),
+            fixed: true,
+        };
+        self.tree.insert(name.into(), Some(parent), samples)
+    }
+    /// Records the pose of a frame in its parent at `time`.
+    ///
+    /// Samples may arrive out of order, a sample at an existing time replaces it. The oldest
+    /// samples are dropped once the history is full, and a static frame only keeps the latest.
+    pub fn insert<const PREV: usize, const CURR: usize>(
+        &mut self,
+        name: &str,
+        time: f64,
+        pose: &Pose<T, PREV, CURR>,
+    ) -> Result<(), FrameError> {
+        if !time.is_finite() {
+            return Err(FrameError::InvalidTime(time));
+        }
+        let edge = self.tree.edge_mut(name)?;
+        let history = match edge.fixed {
+            true => 1,
+            false => self.history,
+        };
+        let samples = &mut edge.samples;
+        let pose = erase(pose);
+        let pos = samples.partition_point(|(t, _)| *t < time);
+        match samples.get(pos) {
+            Some((t, _)) if *t == time => samples[pos] = (time, pose),
+            _ => samples.insert(pos, (time, pose)),
+        }
+        while samples.len() > history {
+            samples.pop_front();
+        }
+        Ok(())
+    }
+    /// Returns the times of the first and last samples of a frame.
+    pub fn range(&self, name: &str) -> Result<Option<(f64, f64)>, FrameError> {
+        let samples = &self.tree.edge(name)?.samples;
+        Ok(samples.front().zip(samples.back()).map(|(a, b)| (a.0, b.0)))
+    }
+    pub fn contains(&self, name: &str) -> bool {
+        self.tree.contains(name)
+    }
+    /// Returns the parent of a frame, `None` for a root.
+    pub fn parent(&self, name: &str) -> Result<Option<&str>, FrameError> {
+        self.tree.parent(name)
+    }
+    /// Returns the names of all frames, in the order they were added.
+    pub fn frames(&self) -> impl Iterator<Item = &str> {
+        self.tree.frames()
+    }
+    /// Returns the pose of a frame in its parent at `time`.
+    fn sample(
+        &self,
+        name: &str,
+        edge: &Samples<T>,
+        time: f64,
+    ) -> Result<Pose<T, 0, 0>, FrameError> {
+        let samples = &edge.samples;
+        let (first, last) = match (samples.front(), samples.back()) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Err(FrameError::NoData(name.to_string())),
+        };
+        if edge.fixed {
+            return Ok(last.1.clone());
+        }
+        if time < first.0 || time > last.0 {
+            let (nearest, distance) = match time < first.0 {
+                true => (first, first.0 - time),
+                false => (last, time - last.0),
+            };
+            if distance > self.extrapolation {
+                return Err(FrameError::Extrapolation {
+                    frame: name.to_string(),
+                    time,
+                    earliest: first.0,
+                    latest: last.0,
+                });
+            }
+            return Ok(nearest.1.clone());
+        }
+        let pos = samples.partition_point(|(t, _)| *t < time);
+        let (t1, after) = &samples[pos];
+        if *t1 == time {
+            return Ok(after.clone());
+        }
+        let (t0, before) = &samples[pos - 1];
+        let s = T::from((time - t0) / (t1 - t0)).unwrap();
+        Ok(before.interpolate(after, s))
+    }
+    /// Returns the pose of frame `to` expressed in frame `from` at `time`, see
+    /// [`FrameGraph::lookup`](super::graph::FrameGraph::lookup).
+    pub fn lookup<const FROM: usize, const TO: usize>(
+        &self,
+        from: &str,
+        to: &str,
+        time: f64,
+    ) -> Result<Pose<T, FROM, TO>, FrameError> {
+        if !time.is_finite() {
+            return Err(FrameError::InvalidTime(time));
+        }
+        self.tree
+            .lookup(from, to, |name, edge| self.sample(name, edge, time))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use matrs::predule::*;
+
+    use super::*;
+    use crate::pose::Point;
+
+    fn at(x: f64) -> Pose<f64, 0, 1> {
+        Pose::from_translation(Vector::new_from_data([x, 0., 0.]))
+    }
+
+    fn x(pose: Pose<f64, 0, 1>) -> f64 {
+        let origin: Vector<f64, 3> = (&pose * Point::origin()).into();
+        origin[0]
+    }
+
+    fn buffer(history: usize) -> TransformBuffer<f64> {
+        let mut buffer = TransformBuffer::new(history);
+        buffer.add_root("world").unwrap();
+        buffer.add_frame("tool", "world").unwrap();
+        buffer
+    }
+
+    #[test]
+    fn interpolates_between_samples() {
+        let mut buffer = buffer(10);
+        buffer.insert("tool", 1., &at(2.)).unwrap();
+        buffer.insert("tool", 0., &at(0.)).unwrap();
+        let pose = buffer.lookup("world", "tool", 0.25).unwrap();
+        assert!((x(pose) - 0.5).abs() < 1e-12);
+        assert_eq!(buffer.range("tool").unwrap(), Some((0., 1.)));
+    }
+
+    #[test]
+    fn rejects_non_finite_times() {
+        let mut buffer = buffer(10);
+        buffer.insert("tool", 0., &at(0.)).unwrap();
+        buffer.insert("tool", 1., &at(1.)).unwrap();
+        for time in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY] {
+            let err = buffer.lookup::<0, 1>("world", "tool", time).unwrap_err();
+            assert!(matches!(err, FrameError::InvalidTime(_)));
+            let err = buffer.insert("tool", time, &at(0.)).unwrap_err();
+            assert!(matches!(err, FrameError::InvalidTime(_)));
+        }
+        assert_eq!(buffer.range("tool").unwrap(), Some((0., 1.)));
+    }
+
+    #[test]
+    fn keeps_at_least_one_sample() {
+        let mut buffer = buffer(0);
+        buffer.insert("tool", 0., &at(0.)).unwrap();
+        buffer.insert("tool", 1., &at(3.)).unwrap();
+        assert_eq!(buffer.range("tool").unwrap(), Some((1., 1.)));
+        assert_eq!(x(buffer.lookup("world", "tool", 1.).unwrap()), 3.);
+    }
+}
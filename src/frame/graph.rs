@@ -0,0 +1,263 @@
+//! A tree of frames only known at runtime.
+//!
+//! Every frame is registered with a parent and its pose in that parent, transforms between any
+//! two frames in the same tree are found by composing along the tree.
+//!
+//! ```
+//! use matrs::predule::*;
+//! use robotics::frame::graph::{FrameError, FrameGraph};
+//! use robotics::pose::{Point, Pose};
+//!
+//! let at = |x, y, z| Pose::<f32, 0, 1>::from_translation(Vector::new_from_data([x, y, z]));
+//! let mut scene = FrameGraph::new();
+//! scene.add_root("world")?;
+//! scene.add_frame("table", "world", &at(1., 0., 0.))?;
+//! scene.add_frame("camera", "world", &at(0., 0., 2.))?;
+//! scene.add_frame("robot", "table", &at(0., 0., 0.5))?;
+//! let robot_in_camera: Pose<f32, 1, 2> = scene.lookup("camera", "robot")?;
+//! let robot: Vector<f32, 3> = (&robot_in_camera * Point::origin()).into();
+//! assert_eq!([robot[0], robot[1], robot[2]], [1., 0., -1.5]);
+//!
+//! scene.update("camera", &at(1., 0., 2.))?;
+//! let robot_in_camera: Pose<f32, 1, 2> = scene.lookup("camera", "robot")?;
+//! let robot: Vector<f32, 3> = (&robot_in_camera * Point::origin()).into();
+//! assert_eq!([robot[0], robot[1], robot[2]], [0., 0., -1.5]);
+//! # Ok::<(), FrameError>(())
+//! ```
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use matrs::{matrix::helpers::rotations::Trig, CompliantNumerical, MatrixInterface};
+
+use crate::pose::Pose;
+use crate::Matrix;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FrameError {
+    UnknownFrame(String),
+    DuplicateFrame(String),
+    /// The frames are in different trees.
+    Disconnected {
+        from: String,
+        to: String,
+    },
+    /// A frame in a [`TransformBuffer`](super::buffer::TransformBuffer) has no samples yet.
+    NoData(String),
+    /// A time given to a [`TransformBuffer`](super::buffer::TransformBuffer) is NaN or
+    /// infinite.
+    InvalidTime(f64),
+    /// The requested time is further outside the samples of a frame than allowed.
+    Extrapolation {
+        frame: String,
+        time: f64,
+        earliest: f64,
+        latest: f64,
+    },
+}
+
+impl Display for FrameError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnknownFrame(name) => write!(f, "unknown frame {name}"),
+            Self::DuplicateFrame(name) => write!(f, "frame {name} already exists"),
+            Self::Disconnected { from, to } => {
+                write!(f, "no path between frames {from} and {to}")
+            }
+            Self::NoData(name) => write!(f, "no samples for frame {name}"),
+            Self::InvalidTime(time) => write!(f, "time {time} is not finite"),
+            Self::Extrapolation {
+                frame,
+                time,
+                earliest,
+                latest,
+            } => write!(
+                f,
+                "time {time} is outside the samples of frame {frame}, [{earliest}, {latest}]"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FrameError {}
+
+#[derive(Clone, Debug)]
+struct Node<E> {
+    name: String,
+    parent: Option<usize>,
+    /// What relates the frame to its parent.
+    edge: E,
+}
+
+/// A forest of named frames, each related to its parent by an edge `E`.
+///
+/// Shared by [`FrameGraph`], where the edges are poses, and
+/// [`TransformBuffer`](super::buffer::TransformBuffer), where they are time stamped samples.
+#[derive(Clone, Debug)]
+pub(crate) struct Tree<E> {
+    nodes: Vec<Node<E>>,
+    index: HashMap<String, usize>,
+}
+
+impl<E> Tree<E> {
+    pub(crate) fn new() -> Self {
+        Self {
+            nodes: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+    fn find(&self, name: &str) -> Result<usize, FrameError> {
+        self.index
+            .get(name)
+            .copied()
+            .ok_or_else(|| FrameError::UnknownFrame(name.to_string()))
+    }
+    /// Adds a frame below `parent`, or a root if there is none.
+    pub(crate) fn insert(
+        &mut self,
+        name: String,
+        parent: Option<&str>,
+        edge: E,
+    ) -> Result<(), FrameError> {
+        let parent = parent.map(|parent| self.find(parent)).transpose()?;
+        if self.index.contains_key(&name) {
+            return Err(FrameError::DuplicateFrame(name));
+        }
+        self.index.insert(name.clone(), self.nodes.len());
+        self.nodes.push(Node { name, parent, edge });
+        Ok(())
+    }
+    pub(crate) fn edge(&self, name: &str) -> Result<&E, FrameError> {
+        Ok(&self.nodes[self.find(name)?].edge)
+    }
+    pub(crate) fn edge_mut(&mut self, name: &str) -> Result<&mut E, FrameError> {
+        let idx = self.find(name)?;
+        Ok(&mut self.nodes[idx].edge)
+    }
+    pub(crate) fn contains(&self, name: &str) -> bool {
+        self.index.contains_key(name)
+    }
+    pub(crate) fn parent(&self, name: &str) -> Result<Option<&str>, FrameError> {
+        let idx = self.find(name)?;
+        Ok(self.nodes[idx]
+            .parent
+            .map(|parent| self.nodes[parent].name.as_str()))
+    }
+    pub(crate) fn frames(&self) -> impl Iterator<Item = &str> {
+        self.nodes.iter().map(|node| node.name.as_str())
+    }
+    /// Returns the root of the tree containing the frame and the pose of the frame in it, `pose`
+    /// gives the pose of a frame in its parent from its name and edge.
+    fn to_root<T: CompliantNumerical + Trig>(
+        &self,
+        mut idx: usize,
+        pose: &impl Fn(&str, &E) -> Result<Pose<T, 0, 0>, FrameError>,
+    ) -> Result<(usize, Pose<T, 0, 0>), FrameError> {
+        let node = &self.nodes[idx];
+        let mut ret = pose(&node.name, &node.edge)?;
+        while let Some(parent) = self.nodes[idx].parent {
+            let node = &self.nodes[parent];
+            ret = pose(&node.name, &node.edge)? * ret;
+            idx = parent;
+        }
+        Ok((idx, ret))
+    }
+    /// Returns the pose of frame `to` expressed in frame `from`, see [`FrameGraph::lookup`].
+    pub(crate) fn lookup<T: CompliantNumerical + Trig, const FROM: usize, const TO: usize>(
+        &self,
+        from: &str,
+        to: &str,
+        pose: impl Fn(&str, &E) -> Result<Pose<T, 0, 0>, FrameError>,
+    ) -> Result<Pose<T, FROM, TO>, FrameError> {
+        let (from_root, from_pose) = self.to_root(self.find(from)?, &pose)?;
+        let (to_root, to_pose) = self.to_root(self.find(to)?, &pose)?;
+        if from_root != to_root {
+            return Err(FrameError::Disconnected {
+                from: from.to_string(),
+                to: to.to_string(),
+            });
+        }
+        let pose = from_pose.inverse() * to_pose;
+        let m: &Matrix<T, 4, 4> = (&pose).into();
+        Ok(Pose::from_matrix(m.clone()))
+    }
+}
+
+/// Returns the identity pose, the pose of a root in itself.
+pub(crate) fn identity<T: CompliantNumerical + Trig>() -> Pose<T, 0, 0> {
+    let mut ret = <Matrix<T, 4, 4>>::new();
+    for i in 0..4 {
+        ret[(i, i)] = T::one();
+    }
+    Pose::from_matrix(ret)
+}
+
+/// Copies a pose, dropping its frames.
+pub(crate) fn erase<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize>(
+    pose: &Pose<T, PREV, CURR>,
+) -> Pose<T, 0, 0> {
+    let m: &Matrix<T, 4, 4> = pose.into();
+    Pose::from_matrix(m.clone())
+}
+
+#[derive(Clone, Debug)]
+pub struct FrameGraph<T: CompliantNumerical + Trig> {
+    /// Every edge is the pose of the frame in its parent.
+    tree: Tree<Pose<T, 0, 0>>,
+}
+
+impl<T: CompliantNumerical + Trig> Default for FrameGraph<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: CompliantNumerical + Trig> FrameGraph<T> {
+    pub fn new() -> Self {
+        Self { tree: Tree::new() }
+    }
+    /// Adds a frame without a parent, the root of a new tree.
+    pub fn add_root(&mut self, name: impl Into<String>) -> Result<(), FrameError> {
+        self.tree.insert(name.into(), None, identity())
+    }
+    /// Adds a frame whose pose in `parent` is `pose`.
+    pub fn add_frame<const PREV: usize, const CURR: usize>(
+        &mut self,
+        name: impl Into<String>,
+        parent: &str,
+        pose: &Pose<T, PREV, CURR>,
+    ) -> Result<(), FrameError> {
+        self.tree.insert(name.into(), Some(parent), erase(pose))
+    }
+    /// Replaces the pose of a frame in its parent, the frames below it move along.
+    pub fn update<const PREV: usize, const CURR: usize>(
+        &mut self,
+        name: &str,
+        pose: &Pose<T, PREV, CURR>,
+    ) -> Result<(), FrameError> {
+        *self.tree.edge_mut(name)? = erase(pose);
+        Ok(())
+    }
+    pub fn contains(&self, name: &str) -> bool {
+        self.tree.contains(name)
+    }
+    /// Returns the parent of a frame, `None` for a root.
+    pub fn parent(&self, name: &str) -> Result<Option<&str>, FrameError> {
+        self.tree.parent(name)
+    }
+    /// Returns the names of all frames, in the order they were added.
+    pub fn frames(&self) -> impl Iterator<Item = &str> {
+        self.tree.frames()
+    }
+    /// Returns the pose of frame `to` expressed in frame `from`, i.e. the transform taking
+    /// coordinates in `to` to coordinates in `from`.
+    ///
+    /// The frame indices of the returned pose are chosen by the caller. The rotations along the
+    /// path are assumed to be orthonormal, as [`Pose::inverse`] only transposes them.
+    pub fn lookup<const FROM: usize, const TO: usize>(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<Pose<T, FROM, TO>, FrameError> {
+        self.tree.lookup(from, to, |_, pose| Ok(pose.clone()))
+    }
+}
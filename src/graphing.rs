@@ -1,4 +1,5 @@
-use crate::pose::{Coord, Pose};
+use crate::planar::PlanarPose;
+use crate::pose::{Point, Pose};
 use matrs::vec::Vector;
 use plotly::common::LegendGroupTitle;
 use plotly::plot::Trace;
@@ -7,8 +8,8 @@ use plotly::{layout::Annotation, Scatter3D};
 pub trait Plottable {
     fn plot(&self) -> (Vec<Box<dyn Trace>>, Vec<Annotation>);
 }
-// Any coordinate expressed in the worldframe is plottable as a point
-impl Plottable for Coord<f32, 0> {
+// Any point expressed in the worldframe is plottable
+impl Plottable for Point<f32, 0> {
     fn plot(&self) -> (Vec<Box<dyn Trace>>, Vec<Annotation>) {
         let mut ret = Vec::new();
 
@@ -41,9 +42,16 @@ impl<const FRAME: usize> Plottable for Pose<f32, 0, FRAME> {
         let mut ret = Vec::new();
         let mut annotations = Vec::new();
 
-        plot_vec(&mut ret, &mut annotations, origin, x, "x");
-        plot_vec(&mut ret, &mut annotations, origin, y, "y");
-        plot_vec(&mut ret, &mut annotations, origin, z, "z");
+        plot_vec(&mut ret, &mut annotations, origin, origin + x, "x");
+        plot_vec(&mut ret, &mut annotations, origin, origin + y, "y");
+        plot_vec(&mut ret, &mut annotations, origin, origin + z, "z");
         (ret, annotations)
     }
 }
+
+// Planar poses are drawn in the xy plane of the world frame
+impl<const FRAME: usize> Plottable for PlanarPose<f32, 0, FRAME> {
+    fn plot(&self) -> (Vec<Box<dyn Trace>>, Vec<Annotation>) {
+        Pose::from(self.clone()).plot()
+    }
+}
@@ -1,7 +1,13 @@
 pub use matrs::predule::*;
 
+pub mod dual_quaternion;
+pub mod euler;
+pub mod frame;
+pub mod lie;
 pub mod link;
+pub mod planar;
 pub mod pose;
+pub mod quaternion;
 pub mod syms;
 
 pub mod decore;
@@ -0,0 +1,480 @@
+//! Exponential and logarithm maps of SO(3) and SE(3).
+//!
+//! Rotations are parametrized by rotation vectors `phi = angle * axis` and rigid motions by twists
+//! `xi = [v; w]`, where `v` is the translational and `w` the rotational part. The Jacobians relate
+//! a perturbation of the parameters to a perturbation of the group element,
+//! `exp(xi + d) ~ exp(J_l(xi) d) exp(xi) ~ exp(xi) exp(J_r(xi) d)`.
+//!
+//! ```
+//! use std::f32::consts::PI;
+//!
+//! use robotics::lie::Twist;
+//! use robotics::pose::Pose;
+//!
+//! let twist = Twist::from_array([1., 0., 0., 0., 0., PI / 2.]);
+//! let pose: Pose<f32, 0, 1> = twist.exp();
+//! let half_way: Pose<f32, 0, 1> = twist.scale(0.5).exp();
+//! assert!((pose.log().w[2] - PI / 2.).abs() < 1e-6);
+//! ```
+use matrs::matrix::helpers::rotations::Trig;
+use matrs::{CompliantNumerical, MatrixInterface, VectorTrait};
+use num_traits::Float;
+
+use crate::pose::Pose;
+use crate::quaternion::Quaternion;
+use crate::{Matrix, Vector};
+
+/// Returns the skew-symmetric matrix `[v]_x`, such that `[v]_x u = v x u`.
+pub fn skew<T: CompliantNumerical>(v: &Vector<T, 3>) -> Matrix<T, 3, 3> {
+    let (x, y, z) = (v[0].clone(), v[1].clone(), v[2].clone());
+    Matrix::new_from_data([
+        [T::zero(), T::zero() - z.clone(), y.clone()],
+        [z, T::zero(), T::zero() - x.clone()],
+        [T::zero() - y, x, T::zero()],
+    ])
+}
+
+/// The inverse of [`skew`], the matrix is assumed to be skew-symmetric.
+pub fn vee<T: CompliantNumerical>(m: &Matrix<T, 3, 3>) -> Vector<T, 3> {
+    Vector::new_from_data([m[(2, 1)].clone(), m[(0, 2)].clone(), m[(1, 0)].clone()])
+}
+
+/// Below this angle the closed form coefficients are replaced by their Taylor expansions.
+fn small<T: Float>() -> T {
+    T::from(1e-4).unwrap()
+}
+
+fn norm<T: Float + CompliantNumerical>(v: &Vector<T, 3>) -> T {
+    (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt()
+}
+
+fn scale<T: CompliantNumerical + Copy, const N: usize>(
+    m: Matrix<T, N, N>,
+    s: T,
+) -> Matrix<T, N, N> {
+    let mut ret = m;
+    for i in 0..N {
+        for j in 0..N {
+            ret[(i, j)] = m[(i, j)] * s;
+        }
+    }
+    ret
+}
+
+fn identity<T: CompliantNumerical>() -> Matrix<T, 3, 3> {
+    let mut ret = <Matrix<T, 3, 3>>::new();
+    for i in 0..3 {
+        ret[(i, i)] = T::one();
+    }
+    ret
+}
+
+/// Returns `I + a [phi]_x + b [phi]_x^2`, the form shared by all of the SO(3) maps.
+fn series<T: CompliantNumerical + Copy>(phi: &Vector<T, 3>, a: T, b: T) -> Matrix<T, 3, 3> {
+    let w = skew(phi);
+    identity() + scale(w, a) + scale(w * w, b)
+}
+
+/// The SO(3) exponential, Rodrigues' formula.
+pub fn so3_exp<T: CompliantNumerical + Float>(phi: &Vector<T, 3>) -> Matrix<T, 3, 3> {
+    let theta = norm(phi);
+    let (a, b) = if theta < small() {
+        let sq = theta * theta;
+        (
+            T::one() - sq / T::from(6).unwrap(),
+            T::from(0.5).unwrap() - sq / T::from(24).unwrap(),
+        )
+    } else {
+        (
+            theta.sin() / theta,
+            (T::one() - theta.cos()) / (theta * theta),
+        )
+    };
+    series(phi, a, b)
+}
+
+/// The SO(3) logarithm, the returned rotation vector has a length in `[0, pi]`.
+///
+/// Goes through a [`Quaternion`], which stays well conditioned for angles close to `pi`.
+pub fn so3_log<T: CompliantNumerical + Trig + Float>(r: &Matrix<T, 3, 3>) -> Vector<T, 3> {
+    let mut q = Quaternion::from(*r);
+    if q.w < T::zero() {
+        q = Quaternion::new(-q.w, -q.x, -q.y, -q.z);
+    }
+    let v = Vector::new_from_data([q.x, q.y, q.z]);
+    let sin = norm(&v);
+    let two = T::one() + T::one();
+    // angle / sin(angle / 2), which tends to 2 / w
+    let factor = if sin < small() {
+        two / q.w
+    } else {
+        two * sin.atan2(q.w) / sin
+    };
+    Vector::new_from_data([v[0] * factor, v[1] * factor, v[2] * factor])
+}
+
+/// The left Jacobian of SO(3), also the matrix `V` in the translational part of the SE(3)
+/// exponential.
+pub fn so3_left_jacobian<T: CompliantNumerical + Float>(phi: &Vector<T, 3>) -> Matrix<T, 3, 3> {
+    let theta = norm(phi);
+    let (a, b) = if theta < small() {
+        let sq = theta * theta;
+        (
+            T::from(0.5).unwrap() - sq / T::from(24).unwrap(),
+            T::one() / T::from(6).unwrap() - sq / T::from(120).unwrap(),
+        )
+    } else {
+        let sq = theta * theta;
+        (
+            (T::one() - theta.cos()) / sq,
+            (theta - theta.sin()) / (sq * theta),
+        )
+    };
+    series(phi, a, b)
+}
+
+/// The inverse of [`so3_left_jacobian`].
+pub fn so3_left_jacobian_inverse<T: CompliantNumerical + Float>(
+    phi: &Vector<T, 3>,
+) -> Matrix<T, 3, 3> {
+    let theta = norm(phi);
+    let b = if theta < small() {
+        T::one() / T::from(12).unwrap() + theta * theta / T::from(720).unwrap()
+    } else {
+        let two = T::one() + T::one();
+        T::one() / (theta * theta) - (T::one() + theta.cos()) / (two * theta * theta.sin())
+    };
+    series(phi, -T::from(0.5).unwrap(), b)
+}
+
+/// The right Jacobian of SO(3), `J_r(phi) = J_l(-phi)`.
+pub fn so3_right_jacobian<T: CompliantNumerical + Float>(phi: &Vector<T, 3>) -> Matrix<T, 3, 3> {
+    so3_left_jacobian(&negate(phi))
+}
+
+/// The inverse of [`so3_right_jacobian`].
+pub fn so3_right_jacobian_inverse<T: CompliantNumerical + Float>(
+    phi: &Vector<T, 3>,
+) -> Matrix<T, 3, 3> {
+    so3_left_jacobian_inverse(&negate(phi))
+}
+
+fn negate<T: CompliantNumerical + Float>(v: &Vector<T, 3>) -> Vector<T, 3> {
+    Vector::new_from_data([-v[0], -v[1], -v[2]])
+}
+
+/// A rotation of `angle` about a unit `axis`.
+#[derive(Clone, Debug)]
+pub struct AxisAngle<T: CompliantNumerical> {
+    pub axis: Vector<T, 3>,
+    pub angle: T,
+}
+
+impl<T: CompliantNumerical + Trig + Float> AxisAngle<T> {
+    /// Creates the rotation, the axis does not need to be normalized.
+    pub fn new(axis: Vector<T, 3>, angle: T) -> Self {
+        let n = norm(&axis);
+        Self {
+            axis: Vector::new_from_data([axis[0] / n, axis[1] / n, axis[2] / n]),
+            angle,
+        }
+    }
+    /// Splits a rotation vector into its axis and angle, a zero vector gives a rotation about x.
+    pub fn from_rotation_vector(phi: &Vector<T, 3>) -> Self {
+        let angle = norm(phi);
+        if angle < T::epsilon() {
+            return Self {
+                axis: Vector::new_from_data([T::one(), T::zero(), T::zero()]),
+                angle: T::zero(),
+            };
+        }
+        Self::new(*phi, angle)
+    }
+    /// Returns `angle * axis`.
+    pub fn rotation_vector(&self) -> Vector<T, 3> {
+        Vector::new_from_data([0, 1, 2].map(|i| self.axis[i] * self.angle))
+    }
+    /// Returns the rotation matrix using Rodrigues' formula.
+    pub fn to_matrix(&self) -> Matrix<T, 3, 3> {
+        series(&self.axis, self.angle.sin(), T::one() - self.angle.cos())
+    }
+}
+
+impl<T: CompliantNumerical + Trig + Float> From<Matrix<T, 3, 3>> for AxisAngle<T> {
+    fn from(value: Matrix<T, 3, 3>) -> Self {
+        Self::from_rotation_vector(&so3_log(&value))
+    }
+}
+
+impl<T: CompliantNumerical + Trig + Float> From<AxisAngle<T>> for Matrix<T, 3, 3> {
+    fn from(value: AxisAngle<T>) -> Self {
+        value.to_matrix()
+    }
+}
+
+impl<T: CompliantNumerical + Trig + Float> From<AxisAngle<T>> for Quaternion<T> {
+    fn from(value: AxisAngle<T>) -> Self {
+        Quaternion::from_axis_angle(value.axis, value.angle)
+    }
+}
+
+/// Twist coordinates of SE(3), `v` is the translational and `w` the rotational part.
+#[derive(Clone, Debug)]
+pub struct Twist<T: CompliantNumerical> {
+    pub v: Vector<T, 3>,
+    pub w: Vector<T, 3>,
+}
+
+impl<T: CompliantNumerical + Trig + Float> Twist<T> {
+    pub fn new(v: Vector<T, 3>, w: Vector<T, 3>) -> Self {
+        Self { v, w }
+    }
+    /// Creates a twist from `[v; w]`.
+    pub fn from_array(xi: [T; 6]) -> Self {
+        Self::new(
+            Vector::new_from_data([xi[0], xi[1], xi[2]]),
+            Vector::new_from_data([xi[3], xi[4], xi[5]]),
+        )
+    }
+    /// Returns `[v; w]`.
+    pub fn to_array(&self) -> [T; 6] {
+        [
+            self.v[0], self.v[1], self.v[2], self.w[0], self.w[1], self.w[2],
+        ]
+    }
+    /// Scales the twist, `twist.scale(t).exp()` moves a fraction `t` along the screw motion.
+    pub fn scale(&self, s: T) -> Self {
+        Self::from_array(self.to_array().map(|el| el * s))
+    }
+    /// The SE(3) exponential, `[exp(w) | J_l(w) v]`.
+    pub fn exp<const PREV: usize, const CURR: usize>(&self) -> Pose<T, PREV, CURR> {
+        let rot = so3_exp(&self.w);
+        let jac = so3_left_jacobian(&self.w);
+        let t = [0, 1, 2].map(|i| (0..3).fold(T::zero(), |acc, j| acc + jac[(i, j)] * self.v[j]));
+        let mut rpr = <Matrix<T, 4, 4>>::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                rpr[(i, j)] = rot[(i, j)];
+            }
+            rpr[(i, 3)] = t[i];
+        }
+        rpr[(3, 3)] = T::one();
+        Pose::from_matrix(rpr)
+    }
+    /// The left Jacobian of SE(3), `[[J, Q], [0, J]]` with `J` the SO(3) left Jacobian of `w`.
+    pub fn left_jacobian(&self) -> Matrix<T, 6, 6> {
+        let jac = so3_left_jacobian(&self.w);
+        let q = self.q();
+        let mut ret = <Matrix<T, 6, 6>>::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                ret[(i, j)] = jac[(i, j)];
+                ret[(i + 3, j + 3)] = jac[(i, j)];
+                ret[(i, j + 3)] = q[(i, j)];
+            }
+        }
+        ret
+    }
+    /// The right Jacobian of SE(3), `J_r(xi) = J_l(-xi)`.
+    pub fn right_jacobian(&self) -> Matrix<T, 6, 6> {
+        self.scale(-T::one()).left_jacobian()
+    }
+    /// The inverse of [`Twist::left_jacobian`], `[[J^-1, -J^-1 Q J^-1], [0, J^-1]]`.
+    pub fn left_jacobian_inverse(&self) -> Matrix<T, 6, 6> {
+        let inv = so3_left_jacobian_inverse(&self.w);
+        let q = inv * self.q() * inv;
+        let mut ret = <Matrix<T, 6, 6>>::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                ret[(i, j)] = inv[(i, j)];
+                ret[(i + 3, j + 3)] = inv[(i, j)];
+                ret[(i, j + 3)] = -q[(i, j)];
+            }
+        }
+        ret
+    }
+    /// The inverse of [`Twist::right_jacobian`].
+    pub fn right_jacobian_inverse(&self) -> Matrix<T, 6, 6> {
+        self.scale(-T::one()).left_jacobian_inverse()
+    }
+    /// The coupling block of the SE(3) Jacobians, see Barfoot, State Estimation for Robotics.
+    fn q(&self) -> Matrix<T, 3, 3> {
+        let (p, r) = (skew(&self.w), skew(&self.v));
+        let theta = norm(&self.w);
+        let (a, b, c) = if theta < small() {
+            (
+                T::one() / T::from(6).unwrap(),
+                T::one() / T::from(24).unwrap(),
+                T::one() / T::from(120).unwrap(),
+            )
+        } else {
+            let (sin, cos) = (theta.sin(), theta.cos());
+            let two = T::one() + T::one();
+            let sq = theta * theta;
+            (
+                (theta - sin) / (sq * theta),
+                (sq + two * cos - two) / (two * sq * sq),
+                (two * theta - T::from(3).unwrap() * sin + theta * cos) / (two * sq * sq * theta),
+            )
+        };
+        let pr = p * r;
+        let rp = r * p;
+        let prp = pr * p;
+        scale(r, T::from(0.5).unwrap())
+            + scale(pr + rp + prp, a)
+            + scale(p * pr + rp * p - scale(prp, T::from(3).unwrap()), b)
+            + scale(prp * p + p * prp, c)
+    }
+}
+
+impl<T: CompliantNumerical + Trig + Float, const PREV: usize, const CURR: usize>
+    Pose<T, PREV, CURR>
+{
+    /// The SE(3) logarithm, the inverse of [`Twist::exp`].
+    pub fn log(&self) -> Twist<T> {
+        let m: &Matrix<T, 4, 4> = self.into();
+        let mut rot = <Matrix<T, 3, 3>>::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                rot[(i, j)] = m[(i, j)];
+            }
+        }
+        let w = so3_log(&rot);
+        let inv = so3_left_jacobian_inverse(&w);
+        let v = [0, 1, 2].map(|i| (0..3).fold(T::zero(), |acc, j| acc + inv[(i, j)] * m[(j, 3)]));
+        Twist::new(Vector::new_from_data(v), w)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::f64::consts::PI;
+
+    use super::*;
+
+    const EPS: f64 = 1e-9;
+
+    fn vector(v: [f64; 3]) -> Vector<f64, 3> {
+        Vector::new_from_data(v)
+    }
+
+    fn assert_close<const N: usize>(lhs: &Matrix<f64, N, N>, rhs: &Matrix<f64, N, N>, eps: f64) {
+        for i in 0..N {
+            for j in 0..N {
+                assert!(
+                    (lhs[(i, j)] - rhs[(i, j)]).abs() < eps,
+                    "element ({i}, {j}): {} != {}",
+                    lhs[(i, j)],
+                    rhs[(i, j)]
+                );
+            }
+        }
+    }
+
+    fn assert_identity<const N: usize>(m: &Matrix<f64, N, N>) {
+        let mut identity = <Matrix<f64, N, N>>::new();
+        for i in 0..N {
+            identity[(i, i)] = 1.;
+        }
+        assert_close(m, &identity, EPS);
+    }
+
+    /// Rotation vectors with angles from zero, through the Taylor expansions, up to close to pi.
+    fn rotation_vectors() -> Vec<Vector<f64, 3>> {
+        let axis = [0.48, -0.6, 0.64];
+        [0., 1e-8, 1e-5, 0.9e-4, 1.1e-4, 0.3, 1.7, 3.1, PI - 1e-5]
+            .iter()
+            .map(|angle| vector(axis.map(|el| el * angle)))
+            .collect()
+    }
+
+    #[test]
+    fn so3_log_inverts_exp() {
+        for phi in rotation_vectors() {
+            let ret = so3_log(&so3_exp(&phi));
+            for i in 0..3 {
+                assert!((ret[i] - phi[i]).abs() < EPS, "angle {}", norm(&phi));
+            }
+        }
+    }
+
+    #[test]
+    fn so3_exp_is_a_rotation() {
+        for phi in rotation_vectors() {
+            let r = so3_exp(&phi);
+            assert_identity(&(r.transpose() * r));
+        }
+    }
+
+    #[test]
+    fn so3_jacobians_invert() {
+        for phi in rotation_vectors().into_iter().filter(|phi| norm(phi) < 3.) {
+            assert_identity(&(so3_left_jacobian(&phi) * so3_left_jacobian_inverse(&phi)));
+            assert_identity(&(so3_right_jacobian(&phi) * so3_right_jacobian_inverse(&phi)));
+        }
+    }
+
+    #[test]
+    fn so3_jacobians_are_continuous() {
+        // Either side of the switch to the Taylor expansions
+        let axis = [0.48, -0.6, 0.64];
+        let below = vector(axis.map(|el| el * 0.99999e-4));
+        let above = vector(axis.map(|el| el * 1.00001e-4));
+        assert_close(&so3_exp(&below), &so3_exp(&above), 1e-8);
+        assert_close(&so3_left_jacobian(&below), &so3_left_jacobian(&above), 1e-8);
+        assert_close(
+            &so3_left_jacobian_inverse(&below),
+            &so3_left_jacobian_inverse(&above),
+            1e-8,
+        );
+    }
+
+    #[test]
+    fn so3_left_jacobian_maps_perturbations() {
+        // exp(phi + d) ~ exp(J_l(phi) d) exp(phi)
+        let d = vector([1e-6, -2e-6, 1.5e-6]);
+        for phi in rotation_vectors().into_iter().filter(|phi| norm(phi) < 3.) {
+            let jac = so3_left_jacobian(&phi);
+            let jd = [0, 1, 2].map(|i| (0..3).fold(0., |acc, j| acc + jac[(i, j)] * d[j]));
+            let lhs = so3_exp(&vector([0, 1, 2].map(|i| phi[i] + d[i])));
+            let rhs = so3_exp(&vector(jd)) * so3_exp(&phi);
+            assert_close(&lhs, &rhs, 1e-10);
+        }
+    }
+
+    fn twists() -> Vec<Twist<f64>> {
+        rotation_vectors()
+            .into_iter()
+            .map(|w| Twist::new(vector([0.3, -1.2, 0.7]), w))
+            .collect()
+    }
+
+    #[test]
+    fn se3_log_inverts_exp() {
+        for twist in twists() {
+            let ret = twist.exp::<0, 1>().log();
+            for (ret, el) in ret.to_array().iter().zip(twist.to_array()) {
+                assert!((ret - el).abs() < 1e-8, "{:?}", twist.to_array());
+            }
+        }
+    }
+
+    #[test]
+    fn se3_jacobians_invert() {
+        for twist in twists().into_iter().filter(|twist| norm(&twist.w) < 3.) {
+            assert_identity(&(twist.left_jacobian() * twist.left_jacobian_inverse()));
+            assert_identity(&(twist.right_jacobian() * twist.right_jacobian_inverse()));
+        }
+    }
+
+    #[test]
+    fn axis_angle_matches_exp() {
+        let phi = vector([0.2, 0.5, -0.4]);
+        let axis_angle = AxisAngle::from_rotation_vector(&phi);
+        assert_close(&axis_angle.to_matrix(), &so3_exp(&phi), EPS);
+        let ret = AxisAngle::from(so3_exp(&phi)).rotation_vector();
+        for i in 0..3 {
+            assert!((ret[i] - phi[i]).abs() < EPS);
+        }
+    }
+}
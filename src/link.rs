@@ -2,61 +2,480 @@ use matrs::{
     matrix::helpers::rotations::*, matrix::rotations::Trig, predule::*, CompliantNumerical,
 };
 
+use std::fmt::Display;
 use std::ops::Mul;
 
-use crate::decore::decorators::ToTex;
+use crate::decore::decorators::{label, ToJulia, ToMathML, ToOctave, ToTypst};
+use crate::decore::matlab::{self, dh_elements, identifiers};
+use crate::decore::table::{self, TableFormat, TableOptions};
 use crate::pose::Pose;
+use crate::syms::Sym;
+
+pub mod chain;
+pub mod description;
+pub mod screw;
 
 // pub struct Link<const IDX: usize,L:CompliantNumerical+Trig,> {
 //      length:
 // }
 
+/// The convention a set of DH parameters is given in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Convention {
+    /// `Rz(theta)*Tz(d)*Tx(a)*Rx(alpha)`, frame `i` sits on the axis of joint `i + 1`.
+    #[default]
+    Standard,
+    /// Craig's `Rx(alpha)*Tx(a)*Rz(theta)*Tz(d)`, frame `i` sits on the axis of joint `i`.
+    ///
+    /// Row `i` holds `alpha_{i-1}` and `a_{i-1}` along with `theta_i` and `d_i`.
+    Modified,
+}
+
+/// The kind of joint described by a row of a [`DHTable`], with its joint variable.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Joint {
+    /// Rotates about z, the joint variable is part of theta.
+    Revolute(&'static str),
+    /// Translates along z, the joint variable is part of d.
+    Prismatic(&'static str),
+    Fixed,
+}
+
+impl Joint {
+    /// Guesses the joint type from which parameters are symbolic, theta takes precedence over d.
+    fn infer(theta: &Sym, d: &Sym) -> Self {
+        let (mut theta_ids, mut d_ids) = (Vec::new(), Vec::new());
+        identifiers(theta, &mut theta_ids);
+        identifiers(d, &mut d_ids);
+        match (theta_ids.first(), d_ids.first()) {
+            (Some(var), _) => Self::Revolute(var),
+            (None, Some(var)) => Self::Prismatic(var),
+            (None, None) => Self::Fixed,
+        }
+    }
+    /// Returns the joint variable, if any.
+    pub fn variable(&self) -> Option<&'static str> {
+        match self {
+            Self::Revolute(var) | Self::Prismatic(var) => Some(var),
+            Self::Fixed => None,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum TableError {
+    /// A row only relates frame `i` to frame `i + 1`.
+    NotAdjacent {
+        prev: usize,
+        curr: usize,
+    },
+    /// The table has no row with the index.
+    MissingRow {
+        row: usize,
+        rows: usize,
+    },
+    Rotation(Error),
+}
+
+impl Display for TableError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotAdjacent { prev, curr } => {
+                write!(f, "frames {prev} and {curr} are not adjacent")
+            }
+            Self::MissingRow { row, rows } => {
+                write!(f, "no row {row} in a table with {rows} rows")
+            }
+            Self::Rotation(e) => write!(f, "{e:?}"),
+        }
+    }
+}
+
+impl std::error::Error for TableError {}
+
+/// One row of a [`DHTable`].
+#[derive(Clone, Debug)]
+pub struct DHRow {
+    params: DHParams<Sym, Sym, Sym, Sym>,
+    joint: Joint,
+}
+
+impl DHRow {
+    pub fn params(&self) -> &DHParams<Sym, Sym, Sym, Sym> {
+        &self.params
+    }
+    pub fn joint(&self) -> &Joint {
+        &self.joint
+    }
+}
+
+/// A table of DH parameters, one row per joint.
+///
+/// Row `i` describes the transform from frame `i` to frame `i + 1`. The joint type of each row
+/// is inferred when the row is created, use [`DHTable::with_joint`] where the guess is wrong.
+#[derive(Clone, Debug)]
 pub struct DHTable {
-    rows: Vec<[String; 4]>,
+    rows: Vec<DHRow>,
+    convention: Convention,
 }
 
 impl DHTable {
     fn new<
-        Theta: CompliantNumerical + Trig + ToTex,
-        D: CompliantNumerical + Trig + ToTex,
-        A: CompliantNumerical + Trig + ToTex,
-        Alpha: CompliantNumerical + Trig + ToTex,
+        Theta: CompliantNumerical + Trig + Into<Sym>,
+        D: CompliantNumerical + Trig + Into<Sym>,
+        A: CompliantNumerical + Trig + Into<Sym>,
+        Alpha: CompliantNumerical + Trig + Into<Sym>,
     >(
         theta: &Theta,
         d: &D,
         a: &A,
         alpha: &Alpha,
+        convention: Convention,
     ) -> Self {
+        let params = DHParams {
+            theta: theta.clone().into(),
+            d: d.clone().into(),
+            a: a.clone().into(),
+            alpha: alpha.clone().into(),
+            convention,
+        };
+        let joint = Joint::infer(&params.theta, &params.d);
         Self {
-            rows: vec![[
-                theta.to_tex(None),
-                d.to_tex(None),
-                a.to_tex(None),
-                alpha.to_tex(None),
-            ]],
+            rows: vec![DHRow { params, joint }],
+            convention,
         }
     }
+    /// Appends the rows of another table.
+    ///
+    /// A table in the other convention is converted first, see [`DHTable::to_convention`], which
+    /// may add a fixed row.
     pub fn extend(mut self, other: Self) -> Self {
+        let other = other.to_convention(self.convention());
         self.rows.extend(other.rows);
         self
     }
+    pub fn convention(&self) -> Convention {
+        self.convention
+    }
+    /// Converts the table to another convention, such that the full chain is unchanged.
+    ///
+    /// The intermediate frames differ between the conventions. Converting to modified DH appends
+    /// a fixed row for the final `a` and `alpha`, converting to standard DH prepends one for the
+    /// first, in both cases only when they are non zero. Such rows are folded back in when
+    /// converting the other way, so a round trip gives back the original table.
+    pub fn to_convention(&self, convention: Convention) -> Self {
+        if convention == self.convention() {
+            return self.clone();
+        }
+        let zero = || Sym::Number(0.);
+        let is_zero = |s: &Sym| matches!(s, Sym::Number(n) if *n == 0.);
+        let identity =
+            |r: &DHRow| r.joint == Joint::Fixed && is_zero(&r.params.theta) && is_zero(&r.params.d);
+        let row = |theta: Sym, d: Sym, a: Sym, alpha: Sym, joint: Joint| DHRow {
+            params: DHParams {
+                theta,
+                d,
+                a,
+                alpha,
+                convention,
+            },
+            joint,
+        };
+
+        // Tx(a)*Rx(alpha) commute, so they can be moved across the row boundary
+        let mut rows = Vec::with_capacity(self.rows.len() + 1);
+        match convention {
+            Convention::Modified => {
+                let (mut a, mut alpha) = (zero(), zero());
+                for (idx, r) in self.rows.iter().enumerate() {
+                    let p = r.params.clone();
+                    // A leading fixed row without theta and d only contributes a and alpha
+                    if !(idx == 0 && self.rows.len() > 1 && identity(r)) {
+                        rows.push(row(p.theta, p.d, a, alpha, r.joint.clone()));
+                    }
+                    (a, alpha) = (p.a, p.alpha);
+                }
+                if !is_zero(&a) || !is_zero(&alpha) {
+                    rows.push(row(zero(), zero(), a, alpha, Joint::Fixed));
+                }
+            }
+            Convention::Standard => {
+                let first = self.rows.first().map(|r| &r.params);
+                if let Some(first) = first.filter(|p| !is_zero(&p.a) || !is_zero(&p.alpha)) {
+                    rows.push(row(
+                        zero(),
+                        zero(),
+                        first.a.clone(),
+                        first.alpha.clone(),
+                        Joint::Fixed,
+                    ));
+                }
+                for (idx, r) in self.rows.iter().enumerate() {
+                    let (a, alpha) = match self.rows.get(idx + 1) {
+                        Some(next) => (next.params.a.clone(), next.params.alpha.clone()),
+                        // A trailing fixed row without theta and d only contributes a and alpha
+                        None if idx > 0 && identity(r) => break,
+                        None => (zero(), zero()),
+                    };
+                    let p = r.params.clone();
+                    rows.push(row(p.theta, p.d, a, alpha, r.joint.clone()));
+                }
+            }
+        }
+        Self { rows, convention }
+    }
+    fn row(&self, row: usize) -> Result<&DHRow, TableError> {
+        self.rows.get(row).ok_or(TableError::MissingRow {
+            row,
+            rows: self.rows.len(),
+        })
+    }
+    /// Overrides the joint type of a row.
+    pub fn with_joint(mut self, row: usize, joint: Joint) -> Result<Self, TableError> {
+        self.row(row)?;
+        self.rows[row].joint = joint;
+        Ok(self)
+    }
+    pub fn rows(&self) -> &[DHRow] {
+        &self.rows
+    }
+    /// Returns the `[theta, d, a, alpha]` parameters of every joint.
+    pub fn params(&self) -> Vec<[Sym; 4]> {
+        self.rows
+            .iter()
+            .map(|row| {
+                let p = &row.params;
+                [p.theta.clone(), p.d.clone(), p.a.clone(), p.alpha.clone()]
+            })
+            .collect()
+    }
+    /// Returns the parameters in the order of the table columns for the convention.
+    fn columns(&self) -> Vec<[Sym; 4]> {
+        self.params()
+            .into_iter()
+            .map(|[theta, d, a, alpha]| match self.convention() {
+                Convention::Standard => [theta, d, a, alpha],
+                Convention::Modified => [alpha, a, theta, d],
+            })
+            .collect()
+    }
+    /// Returns the joint variable of every joint that has one, in order.
+    pub fn joint_variables(&self) -> Vec<&'static str> {
+        self.rows
+            .iter()
+            .filter_map(|row| row.joint.variable())
+            .collect()
+    }
+    /// Regenerates the transform from frame `PREV` to `CURR`, i.e. row `PREV` of the table.
+    ///
+    /// Fails if `CURR` is not `PREV + 1` or the table has no row `PREV`.
+    pub fn pose<const PREV: usize, const CURR: usize>(
+        &self,
+    ) -> Result<Pose<Sym, PREV, CURR>, TableError> {
+        if CURR != PREV + 1 {
+            return Err(TableError::NotAdjacent {
+                prev: PREV,
+                curr: CURR,
+            });
+        }
+        self.row(PREV)?
+            .params
+            .clone()
+            .pose()
+            .map_err(TableError::Rotation)
+    }
+    /// Composes the transforms of the first `N` rows, giving the pose of frame `N` in the base
+    /// frame.
+    ///
+    /// Fails if the table has fewer than `N` rows.
+    pub fn chain<const N: usize>(&self) -> Result<Pose<Sym, 0, N>, TableError> {
+        if N > 0 {
+            self.row(N - 1)?;
+        }
+        let mut ret = Pose::<Sym, 0, 0>::from_translation(Vector::new_from_data([
+            Sym::Number(0.),
+            Sym::Number(0.),
+            Sym::Number(0.),
+        ]));
+        for row in self.rows[..N].iter() {
+            ret = ret
+                * row
+                    .params
+                    .clone()
+                    .pose::<0, 0>()
+                    .map_err(TableError::Rotation)?;
+        }
+        let m: &Matrix<Sym, 4, 4> = (&ret).into();
+        Ok(Pose::from_matrix(m.clone()))
+    }
+    /// Returns the position of the origin of frame `N` in the base frame.
+    pub fn fk<const N: usize>(&self) -> Result<Vector<Sym, 3>, TableError> {
+        Ok(self.chain::<N>()?.fk())
+    }
+    /// Renders the table in the given format, see [`TableOptions`] for the available options.
+    pub fn render(&self, format: TableFormat, options: &TableOptions) -> String {
+        let variables = self.joint_variables();
+        table::render(
+            &self.params(),
+            self.convention(),
+            &variables,
+            format,
+            options,
+        )
+    }
     pub fn to_tex(&self) -> String {
-        let mut ret:String = "\\begin{table}[H]\\label{table:DHParams}\\centering\n\t\\begin{tabular}{|c|c|c|c|c|}\\hline\n".to_string();
-
-        ret += "\t\t$j$\t&\t$\\theta$\t&\t$d$\t&\t$a$\t&\t$\\alpha$\t\\\\\\hline\n";
-        for (idx, row) in self.rows.iter().enumerate() {
-            ret += format!(
-                "\t\t${}$\t&\t${}$\t&\t${}$\t&\t${}$\t&\t${}$\\\\\n",
-                idx + 1,
-                row[0],
-                row[1],
-                row[2],
-                row[3]
-            )
-            .as_str();
+        let options = TableOptions::new()
+            .caption("good caption here")
+            .label("table:DHParams");
+        self.render(TableFormat::Tex, &options)
+    }
+}
+
+impl ToMathML for DHTable {
+    fn to_mathml_internal(&self, _identifier: Option<&'static str>) -> String {
+        let mut ret =
+            "<mtable frame=\"solid\" rowlines=\"solid\" columnlines=\"solid\">".to_string();
+        ret += match self.convention() {
+            Convention::Standard => {
+                "<mtr><mtd><mi>j</mi></mtd><mtd><mi>θ</mi></mtd><mtd><mi>d</mi></mtd>\
+                <mtd><mi>a</mi></mtd><mtd><mi>α</mi></mtd></mtr>"
+            }
+            Convention::Modified => {
+                "<mtr><mtd><mi>j</mi></mtd>\
+                <mtd><msub><mi>α</mi><mrow><mi>j</mi><mo>-</mo><mn>1</mn></mrow></msub></mtd>\
+                <mtd><msub><mi>a</mi><mrow><mi>j</mi><mo>-</mo><mn>1</mn></mrow></msub></mtd>\
+                <mtd><msub><mi>θ</mi><mi>j</mi></msub></mtd>\
+                <mtd><msub><mi>d</mi><mi>j</mi></msub></mtd></mtr>"
+            }
+        };
+        for (idx, row) in self.columns().iter().enumerate() {
+            ret += format!("<mtr><mtd><mn>{}</mn></mtd>", idx + 1).as_str();
+            for el in row.iter() {
+                ret += format!("<mtd>{}</mtd>", el.to_mathml_internal(None)).as_str();
+            }
+            ret += "</mtr>";
+        }
+        ret += "</mtable>";
+        ret
+    }
+}
+
+impl ToTypst for DHTable {
+    /// Renders the table as a figure, rather than an equation.
+    fn to_typst(&self, identifier: Option<&'static str>) -> String {
+        let ret = format!("#figure({})", self.to_typst_internal(identifier));
+        match identifier.map(label) {
+            Some(label) if !label.is_empty() => format!("{ret} <{label}>"),
+            _ => ret,
+        }
+    }
+    fn to_typst_internal(&self, _identifier: Option<&'static str>) -> String {
+        let mut ret = "table(\n\tcolumns: 5,\n\t".to_string();
+        ret += match self.convention() {
+            Convention::Standard => "[$j$], [$theta$], [$d$], [$a$], [$alpha$],\n",
+            Convention::Modified => "[$j$], [$alpha_(j-1)$], [$a_(j-1)$], [$theta_j$], [$d_j$],\n",
+        };
+        for (idx, row) in self.columns().iter().enumerate() {
+            ret += format!("\t[${}$]", idx + 1).as_str();
+            for el in row.iter() {
+                ret += format!(", [${}$]", el.to_typst_internal(None)).as_str();
+            }
+            ret += ",\n";
+        }
+        ret += ")";
+        ret
+    }
+}
+
+impl DHTable {
+    /// Returns the identifiers in the table as variable names, and the transform of every row
+    /// with pi spelled as `pi`. Shared by the Octave and Julia exporters.
+    fn script(&self, pi: &str) -> (Vec<String>, Vec<[[String; 4]; 4]>) {
+        let mut ids = Vec::new();
+        let transforms = self
+            .params()
+            .iter()
+            .map(|params| {
+                for s in params.iter() {
+                    identifiers(s, &mut ids);
+                }
+                dh_elements(params, self.convention(), pi)
+            })
+            .collect();
+        let ids = ids.into_iter().map(matlab::identifier).collect();
+        (ids, transforms)
+    }
+}
+
+/// Writes one transform per row, `T0_1`, `T1_2`, ..., composed into `identifier`, as
+/// [`MatlabScript::dh_table`](crate::decore::matlab::MatlabScript::dh_table) does.
+impl ToOctave for DHTable {
+    fn to_octave(&self, identifier: &'static str) -> String {
+        let (ids, transforms) = self.script("sym(pi)");
+        let mut ret = String::new();
+        if !ids.is_empty() {
+            ret += "pkg load symbolic\n";
+            ret += format!("syms {}\n", ids.join(" ")).as_str();
         }
-        ret += "\t\\hline\n\t\\end{tabular}\n\t\\caption{good caption here}\n\\end{table}";
+        for (idx, elements) in transforms.iter().enumerate() {
+            let rows = elements
+                .iter()
+                .map(|row| row.join(", "))
+                .collect::<Vec<String>>()
+                .join("; ");
+            ret += format!("T{idx}_{} = [{rows}];\n", idx + 1).as_str();
+        }
+        let names = (0..transforms.len())
+            .map(|idx| format!("T{idx}_{}", idx + 1))
+            .collect::<Vec<String>>();
+        ret += format!("{identifier} = simplify({});\n", names.join("*")).as_str();
+        ret
+    }
+}
 
+/// Writes one transform per row, `T0_1`, `T1_2`, ..., composed into `identifier`, as
+/// [`MatlabScript::dh_table`](crate::decore::matlab::MatlabScript::dh_table) does.
+impl ToJulia for DHTable {
+    fn to_julia(&self, identifier: &'static str) -> String {
+        let (ids, transforms) = self.script("π");
+        let mut ret = String::new();
+        if !ids.is_empty() {
+            ret += "using Symbolics\n";
+            ret += format!("@variables {}\n", ids.join(" ")).as_str();
+        }
+        for (idx, elements) in transforms.iter().enumerate() {
+            let rows = elements
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|el| {
+                            // Elements are space separated, so `a -b` would be read as two
+                            // elements, spaces within parentheses are fine
+                            let mut depth = 0i32;
+                            let spaced = el.chars().any(|c| {
+                                match c {
+                                    '(' => depth += 1,
+                                    ')' => depth -= 1,
+                                    _ => {}
+                                }
+                                c == ' ' && depth == 0
+                            });
+                            match spaced {
+                                true => format!("({el})"),
+                                false => el.clone(),
+                            }
+                        })
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                })
+                .collect::<Vec<String>>()
+                .join("; ");
+            ret += format!("T{idx}_{} = [{rows}]\n", idx + 1).as_str();
+        }
+        let names = (0..transforms.len())
+            .map(|idx| format!("T{idx}_{}", idx + 1))
+            .collect::<Vec<String>>();
+        ret += format!("{identifier} = simplify.({})\n", names.join(" * ")).as_str();
         ret
     }
 }
@@ -75,8 +494,10 @@ pub struct DHBuilder<
     d: Option<Dt>,
     a: Option<At>,
     alpha: Option<Alphat>,
+    convention: Convention,
 }
 
+#[derive(Clone, Debug)]
 pub struct DHParams<
     Theta: CompliantNumerical + Trig,
     D: CompliantNumerical + Trig,
@@ -87,6 +508,7 @@ pub struct DHParams<
     d: D,
     a: A,
     alpha: Alpha,
+    convention: Convention,
 }
 impl<
         const THETA: bool,
@@ -121,8 +543,15 @@ impl<
             d: None,
             a: None,
             alpha: None,
+            convention: Convention::Standard,
         }
     }
+    /// Selects the DH convention the parameters are given in, defaults to
+    /// [`Convention::Standard`].
+    pub fn convention(mut self, convention: Convention) -> Self {
+        self.convention = convention;
+        self
+    }
 }
 
 impl<
@@ -141,6 +570,7 @@ impl<
             d: self.d,
             a: self.a,
             alpha: self.alpha,
+            convention: self.convention,
         }
     }
 }
@@ -161,6 +591,7 @@ impl<
             d: Some(val),
             a: self.a,
             alpha: self.alpha,
+            convention: self.convention,
         }
     }
 }
@@ -181,6 +612,7 @@ impl<
             d: self.d,
             a: Some(val),
             alpha: self.alpha,
+            convention: self.convention,
         }
     }
 }
@@ -200,6 +632,7 @@ impl<
             d: self.d,
             a: self.a,
             alpha: Some(val),
+            convention: self.convention,
         }
     }
 }
@@ -217,24 +650,50 @@ impl<
             d: self.d.unwrap(),
             a: self.a.unwrap(),
             alpha: self.alpha.unwrap(),
+            convention: self.convention,
         }
     }
     pub fn to_table(&self) -> DHTable
     where
-        Thetat: ToTex,
-        Dt: ToTex,
-        At: ToTex,
-        Alphat: ToTex,
+        Thetat: Into<Sym>,
+        Dt: Into<Sym>,
+        At: Into<Sym>,
+        Alphat: Into<Sym>,
     {
         DHTable::new(
             &self.theta.clone().unwrap(),
             &self.d.clone().unwrap(),
             &self.a.clone().unwrap(),
             &self.alpha.clone().unwrap(),
+            self.convention,
         )
     }
 }
 
+impl<
+        Theta: CompliantNumerical + Trig,
+        D: CompliantNumerical + Trig,
+        A: CompliantNumerical + Trig,
+        Alpha: CompliantNumerical + Trig,
+    > DHParams<Theta, D, A, Alpha>
+{
+    pub fn theta(&self) -> &Theta {
+        &self.theta
+    }
+    pub fn d(&self) -> &D {
+        &self.d
+    }
+    pub fn a(&self) -> &A {
+        &self.a
+    }
+    pub fn alpha(&self) -> &Alpha {
+        &self.alpha
+    }
+    pub fn convention(&self) -> Convention {
+        self.convention
+    }
+}
+
 impl<
         Theta: CompliantNumerical + Trig,
         D: CompliantNumerical + Trig,
@@ -247,15 +706,136 @@ where
     pub fn pose<const PREV: usize, const CURR: usize>(
         self,
     ) -> Result<Pose<Theta, PREV, CURR>, Error> {
-        let rz = Pose::<Theta, PREV, PREV>::rot(rotz(self.theta)?);
-        let tz: Pose<D, PREV, PREV> = Pose::from_translation(Vector::new_from_data(
-            [D::zero(), D::zero(), self.d].clone(),
-        ));
-
-        let tx = Pose::<A, PREV, PREV>::from_translation(Vector::new_from_data(
-            [self.a, A::zero(), A::zero()].clone(),
-        ));
-        let rx = Pose::<Alpha, PREV, CURR>::rot(rotx(self.alpha)?);
-        Ok(rz * tz * tx * rx)
+        match self.convention {
+            Convention::Standard => Pose::<Theta, PREV, CURR>::from_dh(
+                self.d, self.theta, self.a, self.alpha,
+            ),
+            Convention::Modified => Pose::<Theta, PREV, CURR>::from_modified_dh(
+                self.alpha, self.a, self.theta, self.d,
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(theta: Sym, d: Sym, a: f32, alpha: f32, convention: Convention) -> DHTable {
+        DHBuilder::new()
+            .convention(convention)
+            .theta(theta)
+            .d(d)
+            .a(Sym::Number(a))
+            .alpha(Sym::Number(alpha))
+            .to_table()
+    }
+
+    fn table(convention: Convention) -> DHTable {
+        let q = |name| Sym::Identifier(name);
+        row(q("q_1"), Sym::Number(0.4), 0.2, 1.2, convention)
+            .extend(row(Sym::Number(0.3), q("q_2"), 0.5, -0.7, convention))
+            .extend(row(q("q_3"), Sym::Number(-0.1), 0.3, 0.9, convention))
+    }
+
+    /// Composes every row and substitutes the joint values, giving the numeric end effector pose.
+    fn end_effector(table: &DHTable, values: &[(&str, f32)]) -> [[f32; 4]; 4] {
+        let mut pose = Pose::<Sym, 0, 0>::from_translation(Vector::new_from_data([
+            Sym::Number(0.),
+            Sym::Number(0.),
+            Sym::Number(0.),
+        ]));
+        for row in table.rows() {
+            pose = pose * row.params().clone().pose::<0, 0>().unwrap();
+        }
+        let m: &Matrix<Sym, 4, 4> = (&pose).into();
+        let mut ret = [[0.; 4]; 4];
+        for (r, ret) in ret.iter_mut().enumerate() {
+            for (c, ret) in ret.iter_mut().enumerate() {
+                let el = values.iter().fold(m[(r, c)].clone(), |s, (var, val)| {
+                    s.subs(var, &Sym::Number(*val))
+                });
+                *ret = match el {
+                    Sym::Number(n) => n,
+                    el => panic!("{el} did not evaluate to a number"),
+                };
+            }
+        }
+        ret
+    }
+
+    fn assert_close(lhs: [[f32; 4]; 4], rhs: [[f32; 4]; 4]) {
+        for (lhs, rhs) in lhs.iter().flatten().zip(rhs.iter().flatten()) {
+            assert!((lhs - rhs).abs() < 1e-5, "{lhs:?} != {rhs:?}");
+        }
+    }
+
+    const VALUES: [&[(&str, f32)]; 3] = [
+        &[("q_1", 0.), ("q_2", 0.), ("q_3", 0.)],
+        &[("q_1", 0.7), ("q_2", -0.3), ("q_3", 2.1)],
+        &[("q_1", -1.9), ("q_2", 1.2), ("q_3", -0.4)],
+    ];
+
+    #[test]
+    fn standard_to_modified_keeps_end_effector() {
+        let standard = table(Convention::Standard);
+        let modified = standard.to_convention(Convention::Modified);
+        assert_eq!(modified.convention(), Convention::Modified);
+        assert_eq!(modified.joint_variables(), standard.joint_variables());
+        for values in VALUES {
+            assert_close(
+                end_effector(&standard, values),
+                end_effector(&modified, values),
+            );
+        }
+    }
+
+    #[test]
+    fn modified_to_standard_keeps_end_effector() {
+        let modified = table(Convention::Modified);
+        let standard = modified.to_convention(Convention::Standard);
+        assert_eq!(standard.convention(), Convention::Standard);
+        assert_eq!(standard.joint_variables(), modified.joint_variables());
+        for values in VALUES {
+            assert_close(
+                end_effector(&modified, values),
+                end_effector(&standard, values),
+            );
+        }
+    }
+
+    #[test]
+    fn round_trip_gives_back_the_table() {
+        for convention in [Convention::Standard, Convention::Modified] {
+            let original = table(convention);
+            let other = match convention {
+                Convention::Standard => Convention::Modified,
+                Convention::Modified => Convention::Standard,
+            };
+            let round_trip = original.to_convention(other).to_convention(convention);
+            assert_eq!(round_trip.params(), original.params());
+        }
+    }
+
+    #[test]
+    fn extend_converts_the_other_table() {
+        let standard = table(Convention::Standard);
+        let modified = row(
+            Sym::Identifier("q_4"),
+            Sym::Number(0.2),
+            0.1,
+            0.3,
+            Convention::Modified,
+        );
+        let extended = standard.clone().extend(modified.clone());
+        assert_eq!(extended.convention(), Convention::Standard);
+        let values = [("q_1", 0.7), ("q_2", -0.3), ("q_3", 2.1), ("q_4", 0.5)];
+        let expected = standard
+            .to_convention(Convention::Modified)
+            .extend(modified);
+        assert_close(
+            end_effector(&extended, &values),
+            end_effector(&expected, &values),
+        );
     }
 }
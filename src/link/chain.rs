@@ -0,0 +1,208 @@
+//! Serial kinematic chains with a length only known at runtime.
+//!
+//! The const generic frames of [`Pose`] catch mistakes in hand written chains, but a chain loaded
+//! from a [description](super::description) has no types to spell out. Here frames are identified
+//! by their index instead, frame `i` being the frame after link `i`.
+use std::fmt::Display;
+
+use matrs::CompliantNumerical;
+use matrs::{matrix::helpers::rotations::Error, matrix::rotations::Trig, predule::*};
+
+use crate::decore::matlab::identifiers;
+use crate::frame::graph::identity;
+use crate::pose::Pose;
+use crate::syms::{Operation, Sym};
+
+use super::{DHParams, DHTable, Joint};
+
+#[derive(Debug)]
+pub enum ChainError {
+    /// The number of joint values does not match the number of movable joints.
+    JointCount {
+        expected: usize,
+        found: usize,
+    },
+    /// The parameter driven by the joint of a table row is not the joint variable plus an
+    /// offset, so the joint value can not simply be added to it.
+    NotAffine {
+        row: usize,
+        variable: &'static str,
+    },
+    Rotation(Error),
+}
+
+impl Display for ChainError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::JointCount { expected, found } => {
+                write!(f, "expected {expected} joint values, found {found}")
+            }
+            Self::NotAffine { row, variable } => {
+                write!(f, "row {row} is not {variable} plus an offset")
+            }
+            Self::Rotation(e) => write!(f, "{e:?}"),
+        }
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+/// The parameters of a link and its joint.
+pub type Link<T> = (DHParams<T, T, T, T>, Joint);
+
+/// A serial chain of links, each described by DH parameters and a joint.
+///
+/// The joint values are added to theta for revolute joints and to d for prismatic joints, the
+/// parameters hold the offsets.
+///
+/// ```
+/// use std::f32::consts::PI;
+///
+/// use robotics::link::chain::KinematicChain;
+/// use robotics::link::{DHBuilder, Joint};
+///
+/// let shoulder = DHBuilder::new().theta(0.).d(0.3).a(0.).alpha(PI / 2.).complete();
+/// let elbow = DHBuilder::new().theta(0.).d(0.).a(0.5).alpha(0.).complete();
+/// let chain = KinematicChain::new()
+///     .link(shoulder, Joint::Revolute("q_1"))
+///     .link(elbow, Joint::Revolute("q_2"));
+/// let frames = chain.frames(&[0.1, -0.4]).unwrap();
+/// assert_eq!(frames.len(), 2);
+///
+/// let tool = chain.fk(&[0., 0.]).unwrap();
+/// assert!((tool[0] - 0.5).abs() < 1e-6 && (tool[2] - 0.3).abs() < 1e-6);
+/// assert!(chain.fk(&[0.]).is_err());
+/// ```
+#[derive(Clone, Debug)]
+pub struct KinematicChain<T: CompliantNumerical + Trig> {
+    links: Vec<Link<T>>,
+}
+
+impl<T: CompliantNumerical + Trig> Default for KinematicChain<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: CompliantNumerical + Trig> KinematicChain<T> {
+    pub fn new() -> Self {
+        Self { links: Vec::new() }
+    }
+    /// Appends a link to the end of the chain.
+    pub fn link(mut self, params: DHParams<T, T, T, T>, joint: Joint) -> Self {
+        self.links.push((params, joint));
+        self
+    }
+    pub fn links(&self) -> &[Link<T>] {
+        &self.links
+    }
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+    /// Returns the number of movable joints, i.e. the length of the joint vector.
+    pub fn dof(&self) -> usize {
+        self.links
+            .iter()
+            .filter(|(_, joint)| *joint != Joint::Fixed)
+            .count()
+    }
+    /// Returns `^0T_i` for every frame `i` in `1..=len`, given one value per movable joint.
+    pub fn frames(&self, q: &[T]) -> Result<Vec<Pose<T, 0, 0>>, ChainError> {
+        if q.len() != self.dof() {
+            return Err(ChainError::JointCount {
+                expected: self.dof(),
+                found: q.len(),
+            });
+        }
+        let mut q = q.iter();
+        let mut ret: Vec<Pose<T, 0, 0>> = Vec::with_capacity(self.links.len());
+        for (params, joint) in self.links.iter() {
+            let mut params = params.clone();
+            match joint {
+                Joint::Revolute(_) => params.theta += q.next().unwrap().clone(),
+                Joint::Prismatic(_) => params.d += q.next().unwrap().clone(),
+                Joint::Fixed => {}
+            }
+            let link: Pose<T, 0, 0> = params.pose().map_err(ChainError::Rotation)?;
+            let pose = match ret.last() {
+                Some(prev) => prev.clone() * link,
+                None => link,
+            };
+            ret.push(pose);
+        }
+        Ok(ret)
+    }
+    /// Returns `^0T_n`, the pose of the last frame in the base frame.
+    pub fn end_effector(&self, q: &[T]) -> Result<Pose<T, 0, 0>, ChainError> {
+        Ok(self.frames(q)?.pop().unwrap_or_else(identity))
+    }
+    /// Returns the forward kinematics, the position of the last frame in the base frame.
+    pub fn fk(&self, q: &[T]) -> Result<Vector<T, 3>, ChainError> {
+        Ok(self.end_effector(q)?.fk())
+    }
+}
+
+/// Whether or not `s` is `variable` plus terms that do not depend on it.
+fn affine(s: &Sym, variable: &'static str) -> bool {
+    let independent = |s: &Sym| {
+        let mut ids = Vec::new();
+        identifiers(s, &mut ids);
+        !ids.contains(&variable)
+    };
+    match s {
+        Sym::Identifier(i) => *i == variable,
+        Sym::Operation(o) => match &**o {
+            Operation::Nop(s) => affine(s, variable),
+            Operation::Add(s1, s2) => {
+                (affine(s1, variable) && independent(s2))
+                    || (independent(s1) && affine(s2, variable))
+            }
+            Operation::Sub(s1, s2) => affine(s1, variable) && independent(s2),
+            Operation::Sum(els) => {
+                let mut dependent = els.iter().filter(|s| !independent(s));
+                matches!(
+                    (dependent.next(), dependent.next()),
+                    (Some(s), None) if affine(s, variable)
+                )
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Builds a chain from a symbolic table, removing the joint variables from the parameters.
+///
+/// The joint values passed to the chain then take the place of the joint variables. This requires
+/// the parameter driven by every joint to be its variable plus an offset, e.g. `q_1 - pi/2`, as
+/// the joint values are added to the parameters.
+impl TryFrom<&DHTable> for KinematicChain<Sym> {
+    type Error = ChainError;
+    fn try_from(value: &DHTable) -> Result<Self, Self::Error> {
+        let zero = Sym::Number(0.);
+        let links = value
+            .rows()
+            .iter()
+            .enumerate()
+            .map(|(row, r)| {
+                let mut params = r.params().clone();
+                let driven = match r.joint() {
+                    Joint::Revolute(var) => Some((&mut params.theta, var)),
+                    Joint::Prismatic(var) => Some((&mut params.d, var)),
+                    Joint::Fixed => None,
+                };
+                if let Some((param, variable)) = driven {
+                    if !affine(param, variable) {
+                        return Err(ChainError::NotAffine { row, variable });
+                    }
+                    *param = param.subs(variable, &zero);
+                }
+                Ok((params, r.joint().clone()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { links })
+    }
+}
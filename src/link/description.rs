@@ -0,0 +1,427 @@
+//! Robot description files.
+//!
+//! A description lists the DH parameters of every joint, along with its type, limits and offset.
+//! Parameters are either numbers or expressions in SymPy syntax, e.g. `"pi/2"` or `"q_1"`, which
+//! are parsed into [`Sym`].
+//!
+//! ```toml
+//! name = "planar"
+//! convention = "standard"
+//!
+//! [[joints]]
+//! type = "revolute"
+//! variable = "q_1"
+//! a = "a_1"
+//! limits = [-3.14, 3.14]
+//!
+//! [[joints]]
+//! type = "prismatic"
+//! variable = "d_2"
+//! alpha = "pi/2"
+//! offset = 0.1
+//! ```
+//!
+//! CSV files have one row per joint and a header naming the columns, any of `name`, `type`,
+//! `variable`, `theta`, `d`, `a`, `alpha`, `min`, `max` and `offset`, in any order. TOML and YAML
+//! require the `toml` and `yaml` features.
+use std::fmt::Display;
+use std::path::Path;
+
+use crate::decore::matlab::identifiers;
+use crate::syms::{ParseError, Sym};
+
+use super::chain::{ChainError, KinematicChain};
+use super::{Convention, DHParams, DHRow, DHTable, Joint};
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    #[cfg(feature = "toml")]
+    Toml(toml::de::Error),
+    #[cfg(feature = "yaml")]
+    Yaml(serde_yaml::Error),
+    /// A malformed CSV line, numbered from 1.
+    Csv(usize, String),
+    /// A parameter of the joint, numbered from 1, is not a valid expression.
+    Expression(usize, ParseError),
+    /// The variable of the joint, numbered from 1, does not occur in the parameter it drives, or
+    /// can not be told apart from the other identifiers in it.
+    Variable(usize, String),
+    UnknownJointType(String),
+    UnknownConvention(String),
+    /// The file extension is not one of the supported formats.
+    UnknownFormat(String),
+    /// The format is supported, but the feature of the same name is not enabled.
+    FeatureDisabled(&'static str),
+    NoJoints,
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "{e}"),
+            #[cfg(feature = "toml")]
+            Self::Toml(e) => write!(f, "{e}"),
+            #[cfg(feature = "yaml")]
+            Self::Yaml(e) => write!(f, "{e}"),
+            Self::Csv(line, message) => write!(f, "line {line}: {message}"),
+            Self::Expression(joint, e) => write!(f, "joint {joint}: {e}"),
+            Self::Variable(joint, variable) => {
+                write!(
+                    f,
+                    "joint {joint}: variable {variable} is not in its parameter"
+                )
+            }
+            Self::UnknownJointType(t) => write!(f, "unknown joint type {t}"),
+            Self::UnknownConvention(c) => write!(f, "unknown DH convention {c}"),
+            Self::UnknownFormat(ext) => write!(f, "unknown file format {ext}"),
+            Self::FeatureDisabled(feature) => {
+                write!(f, "loading {feature} requires the {feature} feature")
+            }
+            Self::NoJoints => write!(f, "the description has no joints"),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<std::io::Error> for LoadError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+/// A parameter, either a number or an expression.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(
+    any(feature = "toml", feature = "yaml"),
+    derive(serde::Deserialize),
+    serde(untagged)
+)]
+pub enum Value {
+    Number(f32),
+    Expression(String),
+}
+
+impl Value {
+    fn to_sym(&self) -> Result<Sym, ParseError> {
+        match self {
+            Self::Number(n) => Ok(Sym::Number(*n)),
+            Self::Expression(e) => e.parse(),
+        }
+    }
+}
+
+/// One joint of a [`Description`], every field is optional.
+///
+/// The parameter driven by the joint, theta for revolute and d for prismatic joints, defaults to
+/// the joint variable, which in turn defaults to `q_1`, `q_2`, and so on. When the driven
+/// parameter is given but the variable is not, the variable is the only identifier in the
+/// parameter. The offset is added to the driven parameter.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(any(feature = "toml", feature = "yaml"), derive(serde::Deserialize))]
+pub struct JointDescription {
+    pub name: Option<String>,
+    /// `revolute`, `prismatic` or `fixed`, inferred from the parameters when left out.
+    #[cfg_attr(any(feature = "toml", feature = "yaml"), serde(rename = "type"))]
+    pub joint: Option<String>,
+    pub variable: Option<String>,
+    pub theta: Option<Value>,
+    pub d: Option<Value>,
+    pub a: Option<Value>,
+    pub alpha: Option<Value>,
+    pub limits: Option<[f32; 2]>,
+    pub offset: Option<f32>,
+}
+
+/// The contents of a robot description file.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(any(feature = "toml", feature = "yaml"), derive(serde::Deserialize))]
+pub struct Description {
+    pub name: Option<String>,
+    /// `standard` or `modified`, defaults to `standard`.
+    pub convention: Option<String>,
+    pub joints: Vec<JointDescription>,
+}
+
+/// A joint of a loaded [`Robot`].
+#[derive(Clone, Debug)]
+pub struct JointInfo {
+    pub name: Option<String>,
+    pub joint: Joint,
+    pub limits: Option<(f32, f32)>,
+    pub offset: f32,
+}
+
+/// A robot loaded from a description.
+#[derive(Clone, Debug)]
+pub struct Robot {
+    name: Option<String>,
+    table: DHTable,
+    joints: Vec<JointInfo>,
+}
+
+impl Robot {
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+    pub fn table(&self) -> &DHTable {
+        &self.table
+    }
+    pub fn joints(&self) -> &[JointInfo] {
+        &self.joints
+    }
+    /// Returns the DH parameters of every joint.
+    pub fn params(&self) -> Vec<DHParams<Sym, Sym, Sym, Sym>> {
+        self.table
+            .rows()
+            .iter()
+            .map(|row| row.params().clone())
+            .collect()
+    }
+    /// Builds the kinematic chain of the robot, the joint values are added to the offsets.
+    pub fn chain(&self) -> Result<KinematicChain<Sym>, ChainError> {
+        KinematicChain::try_from(&self.table)
+    }
+    /// Checks that the joint values, one per joint with a variable, are within the limits.
+    ///
+    /// Fails if the number of values differs from the number of movable joints.
+    pub fn within_limits(&self, values: &[f32]) -> Result<bool, ChainError> {
+        let movable = self
+            .joints
+            .iter()
+            .filter(|joint| joint.joint != Joint::Fixed);
+        let expected = movable.clone().count();
+        if values.len() != expected {
+            return Err(ChainError::JointCount {
+                expected,
+                found: values.len(),
+            });
+        }
+        Ok(movable
+            .zip(values)
+            .all(|(joint, value)| match joint.limits {
+                Some((min, max)) => (min..=max).contains(value),
+                None => true,
+            }))
+    }
+
+    /// Loads a description, the format is chosen from the file extension.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let ext = path
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        let contents = std::fs::read_to_string(path)?;
+        match ext.as_str() {
+            #[cfg(feature = "toml")]
+            "toml" => Self::from_toml(&contents),
+            #[cfg(not(feature = "toml"))]
+            "toml" => Err(LoadError::FeatureDisabled("toml")),
+            #[cfg(feature = "yaml")]
+            "yaml" | "yml" => Self::from_yaml(&contents),
+            #[cfg(not(feature = "yaml"))]
+            "yaml" | "yml" => Err(LoadError::FeatureDisabled("yaml")),
+            "csv" => Self::from_csv(&contents),
+            _ => Err(LoadError::UnknownFormat(ext)),
+        }
+    }
+    #[cfg(feature = "toml")]
+    pub fn from_toml(s: &str) -> Result<Self, LoadError> {
+        toml::from_str::<Description>(s)
+            .map_err(LoadError::Toml)?
+            .try_into()
+    }
+    #[cfg(feature = "yaml")]
+    pub fn from_yaml(s: &str) -> Result<Self, LoadError> {
+        serde_yaml::from_str::<Description>(s)
+            .map_err(LoadError::Yaml)?
+            .try_into()
+    }
+    /// Reads a CSV file with one joint per row, the convention is always standard.
+    pub fn from_csv(s: &str) -> Result<Self, LoadError> {
+        Description::from_csv(s)?.try_into()
+    }
+}
+
+/// Splits a CSV line into fields, fields may be quoted to contain commas.
+fn fields(line: &str) -> Vec<String> {
+    let mut ret = vec![String::new()];
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, quoted) {
+            ('"', true) if chars.peek() == Some(&'"') => {
+                chars.next();
+                ret.last_mut().unwrap().push('"');
+            }
+            ('"', _) => quoted = !quoted,
+            (',', false) => ret.push(String::new()),
+            (c, _) => ret.last_mut().unwrap().push(c),
+        }
+    }
+    ret.into_iter().map(|f| f.trim().to_string()).collect()
+}
+
+impl Description {
+    pub fn from_csv(s: &str) -> Result<Self, LoadError> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty());
+        let header = match lines.next() {
+            Some((_, header)) => fields(header).into_iter().map(|h| h.to_lowercase()),
+            None => return Err(LoadError::NoJoints),
+        };
+        let header: Vec<String> = header.collect();
+
+        let mut joints = Vec::new();
+        for (idx, line) in lines {
+            let line_nr = idx + 1;
+            let number = |s: &str| {
+                s.parse::<f32>()
+                    .map_err(|_| LoadError::Csv(line_nr, format!("invalid number {s}")))
+            };
+            let mut joint = JointDescription::default();
+            let (mut min, mut max) = (None, None);
+            for (column, field) in header.iter().zip(fields(line)) {
+                if field.is_empty() {
+                    continue;
+                }
+                let value = || match field.parse::<f32>() {
+                    Ok(n) => Value::Number(n),
+                    Err(_) => Value::Expression(field.clone()),
+                };
+                match column.as_str() {
+                    "name" => joint.name = Some(field.clone()),
+                    "type" => joint.joint = Some(field.clone()),
+                    "variable" => joint.variable = Some(field.clone()),
+                    "theta" => joint.theta = Some(value()),
+                    "d" => joint.d = Some(value()),
+                    "a" => joint.a = Some(value()),
+                    "alpha" => joint.alpha = Some(value()),
+                    "min" => min = Some(number(&field)?),
+                    "max" => max = Some(number(&field)?),
+                    "offset" => joint.offset = Some(number(&field)?),
+                    other => {
+                        return Err(LoadError::Csv(line_nr, format!("unknown column {other}")))
+                    }
+                }
+            }
+            joint.limits = match (min, max) {
+                (Some(min), Some(max)) => Some([min, max]),
+                (None, None) => None,
+                _ => {
+                    return Err(LoadError::Csv(
+                        line_nr,
+                        "both or neither of min and max must be given".to_string(),
+                    ))
+                }
+            };
+            joints.push(joint);
+        }
+        Ok(Self {
+            name: None,
+            convention: None,
+            joints,
+        })
+    }
+}
+
+impl TryFrom<Description> for Robot {
+    type Error = LoadError;
+    fn try_from(value: Description) -> Result<Self, Self::Error> {
+        if value.joints.is_empty() {
+            return Err(LoadError::NoJoints);
+        }
+        let convention = match value.convention.as_deref() {
+            None | Some("standard") => Convention::Standard,
+            Some("modified") | Some("craig") => Convention::Modified,
+            Some(other) => return Err(LoadError::UnknownConvention(other.to_string())),
+        };
+
+        let mut rows = Vec::new();
+        let mut joints = Vec::new();
+        for (idx, joint) in value.joints.into_iter().enumerate() {
+            let param = |value: &Option<Value>| match value {
+                Some(value) => value
+                    .to_sym()
+                    .map_err(|e| LoadError::Expression(idx + 1, e)),
+                None => Ok(Sym::Number(0.)),
+            };
+            // The variable has to occur in the driven parameter when that is given
+            let variable = |driven: &Option<Value>| -> Result<&'static str, LoadError> {
+                let ids = match driven {
+                    Some(_) => {
+                        let mut ids = Vec::new();
+                        identifiers(&param(driven)?, &mut ids);
+                        Some(ids)
+                    }
+                    None => None,
+                };
+                let name = match (&joint.variable, &ids) {
+                    (Some(variable), _) => variable.clone(),
+                    (None, Some(ids)) if ids.len() == 1 => return Ok(ids[0]),
+                    (None, _) => format!("q_{}", idx + 1),
+                };
+                match ids {
+                    Some(ids) if !ids.contains(&name.as_str()) => {
+                        Err(LoadError::Variable(idx + 1, name))
+                    }
+                    _ => Ok(Box::leak(name.into_boxed_str())),
+                }
+            };
+            let kind = match joint.joint.as_deref() {
+                Some("revolute") => Joint::Revolute(variable(&joint.theta)?),
+                Some("prismatic") => Joint::Prismatic(variable(&joint.d)?),
+                Some("fixed") => Joint::Fixed,
+                None => Joint::infer(&param(&joint.theta)?, &param(&joint.d)?),
+                Some(other) => return Err(LoadError::UnknownJointType(other.to_string())),
+            };
+
+            // The parameter driven by the joint defaults to the joint variable
+            let offset = joint.offset.unwrap_or(0.);
+            let driven =
+                |value: &Option<Value>, variable: &'static str| -> Result<Sym, LoadError> {
+                    let s = match value {
+                        Some(_) => param(value)?,
+                        None => Sym::Identifier(variable),
+                    };
+                    Ok(match offset == 0. {
+                        true => s,
+                        false => s + offset,
+                    })
+                };
+            let (theta, d) = match kind {
+                Joint::Revolute(variable) => (driven(&joint.theta, variable)?, param(&joint.d)?),
+                Joint::Prismatic(variable) => (param(&joint.theta)?, driven(&joint.d, variable)?),
+                Joint::Fixed => (param(&joint.theta)?, param(&joint.d)?),
+            };
+
+            rows.push(DHRow {
+                params: DHParams {
+                    theta,
+                    d,
+                    a: param(&joint.a)?,
+                    alpha: param(&joint.alpha)?,
+                    convention,
+                },
+                joint: kind.clone(),
+            });
+            joints.push(JointInfo {
+                name: joint.name,
+                joint: kind,
+                limits: joint.limits.map(|[min, max]| (min, max)),
+                offset,
+            });
+        }
+
+        Ok(Self {
+            name: value.name,
+            table: DHTable { rows, convention },
+            joints,
+        })
+    }
+}
@@ -0,0 +1,148 @@
+//! Serial chains in the product of exponentials formulation.
+//!
+//! Instead of one set of DH parameters per link, each joint is described by its screw axis in the
+//! base frame, with every joint at zero, and the chain by the home configuration `M` of the end
+//! effector. The forward kinematics are then `e^[S_1] q_1 ... e^[S_n] q_n M`.
+//!
+//! ```
+//! use std::f32::consts::PI;
+//!
+//! use matrs::predule::*;
+//! use robotics::link::screw::{ScrewAxis, ScrewChain};
+//! use robotics::pose::Pose;
+//!
+//! let home = Pose::<f32, 0, 2>::from_translation(Vector::new_from_data([2., 0., 0.]));
+//! let home: &Matrix<f32, 4, 4> = (&home).into();
+//! let z = Vector::new_from_data([0., 0., 1.]);
+//! let chain = ScrewChain::new(home.clone())
+//!     .screw(ScrewAxis::revolute(z, Vector::new_from_data([0., 0., 0.])))
+//!     .screw(ScrewAxis::revolute(z, Vector::new_from_data([1., 0., 0.])));
+//! let tool = chain.fk(&[PI / 2., -PI / 2.]).unwrap();
+//! assert!((tool[0] - 1.).abs() < 1e-6 && (tool[1] - 1.).abs() < 1e-6);
+//! ```
+use matrs::CompliantNumerical;
+use matrs::{matrix::rotations::Trig, predule::*};
+
+use crate::lie::skew;
+
+use super::chain::ChainError;
+
+/// A joint axis in the base frame, `w` is the rotational and `v` the translational part.
+///
+/// For a revolute joint `w` is the unit axis and `v = -w x q` for a point `q` on the axis, for a
+/// prismatic joint `w` is zero and `v` is the unit direction of motion.
+#[derive(Clone, Debug)]
+pub struct ScrewAxis<T: CompliantNumerical> {
+    pub w: Vector<T, 3>,
+    pub v: Vector<T, 3>,
+}
+
+impl<T: CompliantNumerical + Trig> ScrewAxis<T> {
+    pub fn new(w: Vector<T, 3>, v: Vector<T, 3>) -> Self {
+        Self { w, v }
+    }
+    /// A revolute joint about the unit `axis` through `point`.
+    pub fn revolute(axis: Vector<T, 3>, point: Vector<T, 3>) -> Self {
+        let w = skew(&point);
+        // -w x q = q x w
+        let v = [0, 1, 2].map(|i| {
+            (0..3).fold(T::zero(), |acc, j| {
+                acc + w[(i, j)].clone() * axis[j].clone()
+            })
+        });
+        Self::new(axis, Vector::new_from_data(v))
+    }
+    /// A prismatic joint moving along the unit `direction`.
+    pub fn prismatic(direction: Vector<T, 3>) -> Self {
+        Self::new(
+            Vector::new_from_data([T::zero(), T::zero(), T::zero()]),
+            direction,
+        )
+    }
+    /// Returns `e^[S] q`, the motion of the joint when moved by `q`.
+    pub fn exp(&self, q: T) -> Matrix<T, 4, 4> {
+        let w = skew(&self.w);
+        let w2 = w.clone() * w.clone();
+        let (s, c) = (q.clone().sine(), q.clone().cosine());
+        let a = T::one() - c;
+        let b = q.clone() - s.clone();
+
+        let mut ret = <Matrix<T, 4, 4>>::new();
+        for i in 0..3 {
+            let mut t = T::zero();
+            for j in 0..3 {
+                let (id, g) = match i == j {
+                    true => (T::one(), q.clone()),
+                    false => (T::zero(), T::zero()),
+                };
+                ret[(i, j)] = id + s.clone() * w[(i, j)].clone() + a.clone() * w2[(i, j)].clone();
+                // (I q + (1 - cos q) [w] + (q - sin q) [w]^2) v
+                let g = g + a.clone() * w[(i, j)].clone() + b.clone() * w2[(i, j)].clone();
+                t += g * self.v[j].clone();
+            }
+            ret[(i, 3)] = t;
+        }
+        ret[(3, 3)] = T::one();
+        ret
+    }
+}
+
+/// A serial chain given by the screw axes of its joints and the home configuration `M`.
+#[derive(Clone, Debug)]
+pub struct ScrewChain<T: CompliantNumerical + Trig> {
+    screws: Vec<ScrewAxis<T>>,
+    home: Matrix<T, 4, 4>,
+}
+
+impl<T: CompliantNumerical + Trig> ScrewChain<T> {
+    /// Creates a chain without joints, `home` is the end effector pose with every joint at zero.
+    pub fn new(home: Matrix<T, 4, 4>) -> Self {
+        Self {
+            screws: Vec::new(),
+            home,
+        }
+    }
+    /// Appends a joint to the end of the chain.
+    pub fn screw(mut self, screw: ScrewAxis<T>) -> Self {
+        self.screws.push(screw);
+        self
+    }
+    pub fn screws(&self) -> &[ScrewAxis<T>] {
+        &self.screws
+    }
+    pub fn home(&self) -> &Matrix<T, 4, 4> {
+        &self.home
+    }
+    pub fn len(&self) -> usize {
+        self.screws.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.screws.is_empty()
+    }
+    /// Returns `e^[S_1] q_1 ... e^[S_n] q_n M`, given one value per joint.
+    pub fn end_effector(&self, q: &[T]) -> Result<Matrix<T, 4, 4>, ChainError> {
+        if q.len() != self.screws.len() {
+            return Err(ChainError::JointCount {
+                expected: self.screws.len(),
+                found: q.len(),
+            });
+        }
+        Ok(self
+            .screws
+            .iter()
+            .zip(q)
+            .rev()
+            .fold(self.home.clone(), |acc, (screw, q)| {
+                screw.exp(q.clone()) * acc
+            }))
+    }
+    /// Returns the forward kinematics, the position of the end effector in the base frame.
+    pub fn fk(&self, q: &[T]) -> Result<Vector<T, 3>, ChainError> {
+        let m = self.end_effector(q)?;
+        Ok(Vector::new_from_data([
+            m[(0, 3)].clone(),
+            m[(1, 3)].clone(),
+            m[(2, 3)].clone(),
+        ]))
+    }
+}
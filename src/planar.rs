@@ -0,0 +1,338 @@
+//! Poses in the plane.
+//!
+//! A [`PlanarPose`] is the 3x3 homogeneous analogue of [`Pose`], with the same frame const
+//! generics. Planar arms and mobile bases only rotate about z, so the symbolic expressions stay a
+//! third of the size of the full 4x4 transforms.
+//!
+//! ```
+//! use matrs::predule::*;
+//! use robotics::link::Joint;
+//! use robotics::planar::{PlanarChain, PlanarError, PlanarPose};
+//! use robotics::pose::Pose;
+//! use robotics::sym;
+//! use robotics::syms::Sym;
+//!
+//! let arm = PlanarChain::new()
+//!     .link(sym!(0.), sym!("l_1"), Joint::Revolute("q_1"))
+//!     .link(sym!(0.), sym!("l_2"), Joint::Revolute("q_2"))
+//!     .link(sym!(0.), sym!("l_3"), Joint::Revolute("q_3"));
+//! let tool = arm.fk(&[sym!("q_1"), sym!("q_2"), sym!("q_3")]).unwrap();
+//!
+//! // Only poses in the xy plane convert without loss
+//! let lifted = Pose::<f32, 0, 1>::from_translation(Vector::new_from_data([1., 2., 3.]));
+//! let planar: Result<PlanarPose<f32, 0, 1>, _> = (&lifted).try_into();
+//! assert_eq!(planar.unwrap_err(), PlanarError::Translation);
+//! assert_eq!(lifted.project_xy().fk()[1], 2.);
+//! ```
+use std::fmt::Display;
+use std::ops::Mul;
+
+use matrs::matrix::helpers::rotations::Trig;
+use matrs::{CompliantNumerical, MatrixInterface, VectorTrait};
+use num_traits::Float;
+
+use crate::link::chain::ChainError;
+use crate::link::Joint;
+use crate::pose::Pose;
+use crate::{Matrix, Vector};
+
+/// The pose of frame `CURR` expressed in frame `PREV`, both in the xy plane.
+#[derive(Clone, Debug)]
+pub struct PlanarPose<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize> {
+    rpr: Matrix<T, 3, 3>,
+}
+
+impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize> PlanarPose<T, PREV, CURR> {
+    /// Rotates by `theta` about z and then translates by `[x, y]`.
+    pub fn new(theta: T, x: T, y: T) -> Self {
+        let (c, s) = (theta.clone().cosine(), theta.sine());
+        Self {
+            rpr: Matrix::new_from_data([
+                [c.clone(), T::zero() - s.clone(), x],
+                [s, c, y],
+                [T::zero(), T::zero(), T::one()],
+            ]),
+        }
+    }
+    pub fn rot(theta: T) -> Self {
+        Self::new(theta, T::zero(), T::zero())
+    }
+    pub fn from_translation(t: Vector<T, 2>) -> Self {
+        Self::new(T::zero(), t[0].clone(), t[1].clone())
+    }
+    /// The planar counterpart of [`Pose::from_dh`], `Rz(theta)*Tx(a)`.
+    pub fn from_link(theta: T, a: T) -> Self {
+        let (c, s) = (theta.clone().cosine(), theta.sine());
+        Self {
+            rpr: Matrix::new_from_data([
+                [c.clone(), T::zero() - s.clone(), a.clone() * c.clone()],
+                [s.clone(), c, a * s],
+                [T::zero(), T::zero(), T::one()],
+            ]),
+        }
+    }
+    pub fn translation(&self) -> Vector<T, 2> {
+        Vector::new_from_data([self.rpr[(0, 2)].clone(), self.rpr[(1, 2)].clone()])
+    }
+    /// Returns the inverse transform, `[R^T | -R^T t]`.
+    pub fn inverse(&self) -> PlanarPose<T, CURR, PREV> {
+        let mut rpr = <Matrix<T, 3, 3>>::new();
+        for i in 0..2 {
+            let mut t = T::zero();
+            for j in 0..2 {
+                rpr[(i, j)] = self.rpr[(j, i)].clone();
+                t -= self.rpr[(j, i)].clone() * self.rpr[(j, 2)].clone();
+            }
+            rpr[(i, 2)] = t;
+        }
+        rpr[(2, 2)] = T::one();
+        PlanarPose { rpr }
+    }
+}
+
+impl<T: CompliantNumerical + Trig + Float, const PREV: usize, const CURR: usize>
+    PlanarPose<T, PREV, CURR>
+{
+    /// Returns the rotation angle about z, in `[-pi, pi]`.
+    pub fn angle(&self) -> T {
+        self.rpr[(1, 0)].atan2(self.rpr[(0, 0)])
+    }
+}
+
+impl<T: CompliantNumerical + Trig, const CURR: usize> PlanarPose<T, 0, CURR> {
+    /// Returns the forward kinematics for the system.
+    pub fn fk(self) -> Vector<T, 2> {
+        self.translation()
+    }
+}
+
+impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize, const OTHER: usize>
+    Mul<PlanarPose<T, CURR, OTHER>> for PlanarPose<T, PREV, CURR>
+{
+    type Output = PlanarPose<T, PREV, OTHER>;
+    fn mul(self, rhs: PlanarPose<T, CURR, OTHER>) -> Self::Output {
+        PlanarPose {
+            rpr: self.rpr * rhs.rpr,
+        }
+    }
+}
+
+/// Embeds the pose in the xy plane of 3D space.
+impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize>
+    From<PlanarPose<T, PREV, CURR>> for Pose<T, PREV, CURR>
+{
+    fn from(value: PlanarPose<T, PREV, CURR>) -> Self {
+        let mut rpr = <Matrix<T, 4, 4>>::new();
+        for i in 0..2 {
+            for j in 0..2 {
+                rpr[(i, j)] = value.rpr[(i, j)].clone();
+            }
+            rpr[(i, 3)] = value.rpr[(i, 2)].clone();
+        }
+        rpr[(2, 2)] = T::one();
+        rpr[(3, 3)] = T::one();
+        Pose::from_matrix(rpr)
+    }
+}
+
+/// Ways in which a [`Pose`] can fail to lie in the xy plane.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanarError {
+    /// The rotation is not about z alone.
+    Rotation,
+    /// The translation along z is not zero.
+    Translation,
+}
+
+impl Display for PlanarError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Rotation => write!(f, "the rotation is not about z"),
+            Self::Translation => write!(f, "the translation along z is not zero"),
+        }
+    }
+}
+
+impl std::error::Error for PlanarError {}
+
+impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize> Pose<T, PREV, CURR> {
+    /// Projects the pose onto the xy plane, dropping any rotation about x and y and the
+    /// translation along z. Use [`PlanarPose::from_pose`] to check that nothing is dropped.
+    pub fn project_xy(&self) -> PlanarPose<T, PREV, CURR> {
+        let m: &Matrix<T, 4, 4> = self.into();
+        let mut rpr = <Matrix<T, 3, 3>>::new();
+        for i in 0..2 {
+            for j in 0..2 {
+                rpr[(i, j)] = m[(i, j)].clone();
+            }
+            rpr[(i, 2)] = m[(i, 3)].clone();
+        }
+        rpr[(2, 2)] = T::one();
+        PlanarPose { rpr }
+    }
+}
+
+impl<T: CompliantNumerical + Trig + Float, const PREV: usize, const CURR: usize>
+    PlanarPose<T, PREV, CURR>
+{
+    /// Converts a pose that only rotates about z and does not translate along z, every element
+    /// may differ from that by at most `tolerance`.
+    pub fn from_pose(pose: &Pose<T, PREV, CURR>, tolerance: T) -> Result<Self, PlanarError> {
+        let m: &Matrix<T, 4, 4> = pose.into();
+        let off_axis = [
+            m[(0, 2)],
+            m[(1, 2)],
+            m[(2, 0)],
+            m[(2, 1)],
+            m[(2, 2)] - T::one(),
+        ];
+        if off_axis.iter().any(|el| el.abs() > tolerance) {
+            return Err(PlanarError::Rotation);
+        }
+        if m[(2, 3)].abs() > tolerance {
+            return Err(PlanarError::Translation);
+        }
+        Ok(pose.project_xy())
+    }
+}
+
+/// Converts a pose in the xy plane, with a tolerance of the square root of the machine epsilon,
+/// see [`PlanarPose::from_pose`].
+impl<T: CompliantNumerical + Trig + Float, const PREV: usize, const CURR: usize>
+    TryFrom<&Pose<T, PREV, CURR>> for PlanarPose<T, PREV, CURR>
+{
+    type Error = PlanarError;
+    fn try_from(value: &Pose<T, PREV, CURR>) -> Result<Self, Self::Error> {
+        Self::from_pose(value, T::epsilon().sqrt())
+    }
+}
+
+impl<'a, T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize>
+    From<&'a PlanarPose<T, PREV, CURR>> for &'a Matrix<T, 3, 3>
+{
+    fn from(val: &'a PlanarPose<T, PREV, CURR>) -> Self {
+        &val.rpr
+    }
+}
+
+impl<T: CompliantNumerical + Trig + Display, const PREV: usize, const CURR: usize> Display
+    for PlanarPose<T, PREV, CURR>
+where
+    Matrix<T, 3, 3>: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.rpr)
+    }
+}
+
+/// A serial chain of links in the plane, each rotating by theta and then extending by a.
+///
+/// The joint values are added to theta for revolute joints and to a for prismatic joints, which
+/// slide along the link.
+#[derive(Clone, Debug)]
+pub struct PlanarChain<T: CompliantNumerical + Trig> {
+    links: Vec<(T, T, Joint)>,
+}
+
+impl<T: CompliantNumerical + Trig> Default for PlanarChain<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: CompliantNumerical + Trig> PlanarChain<T> {
+    pub fn new() -> Self {
+        Self { links: Vec::new() }
+    }
+    /// Appends a link to the end of the chain.
+    pub fn link(mut self, theta: T, a: T, joint: Joint) -> Self {
+        self.links.push((theta, a, joint));
+        self
+    }
+    pub fn links(&self) -> &[(T, T, Joint)] {
+        &self.links
+    }
+    pub fn len(&self) -> usize {
+        self.links.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.links.is_empty()
+    }
+    /// Returns the number of movable joints, i.e. the length of the joint vector.
+    pub fn dof(&self) -> usize {
+        self.links
+            .iter()
+            .filter(|(_, _, joint)| *joint != Joint::Fixed)
+            .count()
+    }
+    /// Returns `^0T_i` for every frame `i` in `1..=len`, given one value per movable joint.
+    pub fn frames(&self, q: &[T]) -> Result<Vec<PlanarPose<T, 0, 0>>, ChainError> {
+        if q.len() != self.dof() {
+            return Err(ChainError::JointCount {
+                expected: self.dof(),
+                found: q.len(),
+            });
+        }
+        let mut q = q.iter();
+        let mut ret: Vec<PlanarPose<T, 0, 0>> = Vec::with_capacity(self.links.len());
+        for (theta, a, joint) in self.links.iter() {
+            let (mut theta, mut a) = (theta.clone(), a.clone());
+            match joint {
+                Joint::Revolute(_) => theta += q.next().unwrap().clone(),
+                Joint::Prismatic(_) => a += q.next().unwrap().clone(),
+                Joint::Fixed => {}
+            }
+            let link = PlanarPose::from_link(theta, a);
+            let pose = match ret.last() {
+                Some(prev) => prev.clone() * link,
+                None => link,
+            };
+            ret.push(pose);
+        }
+        Ok(ret)
+    }
+    /// Returns `^0T_n`, the pose of the last frame in the base frame.
+    pub fn end_effector(&self, q: &[T]) -> Result<PlanarPose<T, 0, 0>, ChainError> {
+        Ok(self
+            .frames(q)?
+            .pop()
+            .unwrap_or_else(|| PlanarPose::rot(T::zero())))
+    }
+    /// Returns the forward kinematics, the position of the last frame in the base frame.
+    pub fn fk(&self, q: &[T]) -> Result<Vector<T, 2>, ChainError> {
+        Ok(self.end_effector(q)?.fk())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use matrs::matrix::helpers::rotations::rotx;
+
+    use super::*;
+
+    #[test]
+    fn converts_poses_in_the_plane() {
+        let planar = PlanarPose::<f32, 0, 1>::new(0.7, 1., -2.);
+        let pose: Pose<f32, 0, 1> = planar.clone().into();
+        let ret = PlanarPose::try_from(&pose).unwrap();
+        assert!((ret.angle() - 0.7).abs() < 1e-6);
+        assert_eq!(ret.translation()[0], 1.);
+        assert_eq!(ret.translation()[1], -2.);
+    }
+
+    #[test]
+    fn rejects_poses_out_of_the_plane() {
+        let tilted = Pose::<f32, 0, 1>::rot(rotx(0.1f32).unwrap());
+        let err = PlanarPose::try_from(&tilted).unwrap_err();
+        assert_eq!(err, PlanarError::Rotation);
+        // A tilt within the tolerance is accepted
+        let tilted = Pose::<f32, 0, 1>::rot(rotx(1e-5f32).unwrap());
+        assert!(PlanarPose::from_pose(&tilted, 1e-4).is_ok());
+        assert!(PlanarPose::from_pose(&tilted, 1e-6).is_err());
+
+        let lifted = Pose::<f32, 0, 1>::from_translation(Vector::new_from_data([0., 0., 0.5]));
+        let err = PlanarPose::try_from(&lifted).unwrap_err();
+        assert_eq!(err, PlanarError::Translation);
+        assert_eq!(lifted.project_xy().translation()[0], 0.);
+    }
+}
@@ -1,15 +1,96 @@
 use std::fmt::Display;
 
-use matrs::matrix::helpers::rotations::{rotx, roty, rotz, Error, Trig};
+use matrs::matrix::helpers::rotations::{rotx, rotz, Error, Trig};
 use matrs::{CompliantNumerical, MatrixInterface, VectorTrait};
+use num_traits::Float;
 
+use super::euler::{self, EulerOrder, Rotation};
+use super::lie::{so3_exp, so3_log, Twist};
+use super::quaternion::Quaternion;
 use super::{Matrix, Vector};
 
-#[derive(Clone)]
-pub struct Coord<T: CompliantNumerical, const FRAME: usize> {
+/// A position expressed in `FRAME`, transforming it applies both rotation and translation.
+///
+/// The homogeneous component is 1.
+#[derive(Clone, Debug)]
+pub struct Point<T: CompliantNumerical, const FRAME: usize> {
     pub(crate) rpr: Vector<T, 4>,
 }
 
+/// A free vector expressed in `FRAME`, such as a displacement or an axis, transforming it only
+/// applies the rotation.
+///
+/// The homogeneous component is 0.
+#[derive(Clone, Debug)]
+pub struct Direction<T: CompliantNumerical, const FRAME: usize> {
+    pub(crate) rpr: Vector<T, 4>,
+}
+
+/// The former name of [`Direction`], which did not tell it apart from a [`Point`].
+#[deprecated(note = "use `Direction` for free vectors or `Point` for positions")]
+pub type Coord<T, const FRAME: usize> = Direction<T, FRAME>;
+
+/// Vector algebra, only defined between directions expressed in the same frame.
+impl<T: CompliantNumerical, const FRAME: usize> Direction<T, FRAME> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self {
+            rpr: Vector::new_from_data([x, y, z, T::zero()]),
+        }
+    }
+    /// Interprets the vector as a direction, see [`Point::from_vector`] for positions.
+    pub fn from_vector(v: Vector<T, 3>) -> Self {
+        Self::new(v[0].clone(), v[1].clone(), v[2].clone())
+    }
+    pub fn dot(&self, other: &Self) -> T {
+        (0..3).fold(T::zero(), |acc, i| {
+            acc + self.rpr[i].clone() * other.rpr[i].clone()
+        })
+    }
+    pub fn cross(&self, other: &Self) -> Self {
+        let (a, b) = (&self.rpr, &other.rpr);
+        let el = |i: usize, j: usize| a[i].clone() * b[j].clone() - a[j].clone() * b[i].clone();
+        Self {
+            rpr: Vector::new_from_data([el(1, 2), el(2, 0), el(0, 1), T::zero()]),
+        }
+    }
+    pub fn norm(&self) -> T {
+        T::sqrt(self.dot(self))
+    }
+    pub fn normalize(&self) -> Self {
+        self.clone() * (T::one() / self.norm())
+    }
+    /// Returns the component of `self` along `other`.
+    pub fn project_onto(&self, other: &Self) -> Self {
+        other.clone() * (self.dot(other) / other.dot(other))
+    }
+}
+
+impl<T: CompliantNumerical + Float, const FRAME: usize> Direction<T, FRAME> {
+    /// Returns the angle between the vectors, in `[0, pi]`.
+    pub fn angle(&self, other: &Self) -> T {
+        self.cross(other).norm().atan2(self.dot(other))
+    }
+}
+
+impl<T: CompliantNumerical, const FRAME: usize> Point<T, FRAME> {
+    pub fn new(x: T, y: T, z: T) -> Self {
+        Self {
+            rpr: Vector::new_from_data([x, y, z, T::one()]),
+        }
+    }
+    /// Interprets the vector as a position, see [`Direction::from_vector`] for free vectors.
+    pub fn from_vector(v: Vector<T, 3>) -> Self {
+        Self::new(v[0].clone(), v[1].clone(), v[2].clone())
+    }
+    /// The origin of `FRAME`.
+    pub fn origin() -> Self {
+        Self::new(T::zero(), T::zero(), T::zero())
+    }
+}
+
+/// The pose of frame `CURR` expressed in frame `PREV`.
+///
+/// Frames are identified by index, see [`frame`](crate::frame) for frames known by name.
 #[derive(Clone, Debug)]
 pub struct Pose<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize> {
     rpr: Matrix<T, 4, 4>,
@@ -18,17 +99,19 @@ pub struct Pose<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usi
 impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize> Pose<T, PREV, CURR> {
     /// Converts to a series of 0..end rotations. For 3D this would be XYZ.
     ///
-    /// return the pose, the axis of rotation and the full rotation matrix
+    /// return the pose, the normalised angle vector and the full rotation matrix. Note that the
+    /// normalised angles are not the axis of the composite rotation, see
+    /// [`Pose::from_euler`] for other conventions.
     pub fn from_angles(
         angles: Vector<T, 3>,
     ) -> Result<(Self, Vector<T, 3>, Matrix<T, 3, 3>), Error> {
         let mag = angles.magnitude();
         let rot_axis = angles.clone() / mag;
-        let rx: Matrix<T, 3, 3> = rotx(angles[0].clone())?;
-        let ry: Matrix<T, 3, 3> = roty(angles[1].clone())?;
-        let rz: Matrix<T, 3, 3> = rotz(angles[2].clone())?;
-        let rxy = rx * ry;
-        let rxyz = rxy * rz;
+        let rxyz = euler::to_matrix(
+            [angles[0].clone(), angles[1].clone(), angles[2].clone()],
+            EulerOrder::XYZ,
+            Rotation::Intrinsic,
+        )?;
         Ok((Self::rot(rxyz.clone()), rot_axis, rxyz))
     }
     /// Converts a translation vector to a pose
@@ -76,15 +159,56 @@ impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize> Pose<T,
 
         Ok(rz * tz * tx * rx)
     }
+    /// Converts [modified DH parameters](https://en.wikipedia.org/wiki/Denavit%E2%80%93Hartenberg_parameters#Modified_DH_parameters),
+    /// as used by Craig, to a pose
+    ///
+    /// Returns the composite transformation `Rx(alpha)*Tx(a)*Rz(theta)*Tz(d)`
+    pub fn from_modified_dh<
+        Alpha: CompliantNumerical + Trig,
+        A: CompliantNumerical + Trig,
+        THETA,
+        D: CompliantNumerical + Trig,
+    >(
+        alpha: Alpha,
+        a: A,
+        theta: THETA,
+        d: D,
+    ) -> Result<Pose<THETA, PREV, CURR>, Error>
+    where
+        THETA: CompliantNumerical
+            + Trig
+            + Mul<T, Output = THETA>
+            + Mul<Alpha, Output = THETA>
+            + Mul<A, Output = THETA>
+            + Mul<D, Output = THETA>,
+    {
+        // Start from the identity such that the composite is expressed in THETA
+        let identity = Pose::<THETA, PREV, PREV>::from_translation(Vector::new_from_data(
+            [THETA::zero(), THETA::zero(), THETA::zero()].clone(),
+        ));
+        let rx = Pose::<Alpha, PREV, PREV>::rot(rotx(alpha)?);
+        let tx = Pose::<A, PREV, PREV>::from_translation(Vector::new_from_data(
+            [a, A::zero(), A::zero()].clone(),
+        ));
+        let rz = Pose::<THETA, PREV, PREV>::rot(rotz(theta)?);
+        let tz: Pose<D, PREV, CURR> =
+            Pose::from_translation(Vector::new_from_data([D::zero(), D::zero(), d].clone()));
+
+        Ok(identity * rx * tx * rz * tz)
+    }
+    pub(crate) fn from_matrix(rpr: Matrix<T, 4, 4>) -> Self {
+        Self { rpr }
+    }
     pub fn decompose(self) -> (Matrix<T, 3, 3>, Vector<T, 3>) {
         (self.rotation(), self.translation())
     }
+    /// The translation is the last column, `[t_x, t_y, t_z]`.
     fn translation(&self) -> Vector<T, 3> {
         Vector::new_from_data(
             [
-                self.rpr[(3, 0)].clone(),
-                self.rpr[(3, 1)].clone(),
-                self.rpr[(3, 2)].clone(),
+                self.rpr[(0, 3)].clone(),
+                self.rpr[(1, 3)].clone(),
+                self.rpr[(2, 3)].clone(),
             ]
             .clone(),
         )
@@ -113,17 +237,37 @@ impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize> Pose<T,
         target[(3, 3)] = T::one();
         Self { rpr: target }
     }
+    /// Returns the inverse transform, `[R^T | -R^T t]`, which maps frame `PREV` back to `CURR`.
+    ///
+    /// Only the transpose is used, so the rotation part is assumed to be orthonormal.
+    pub fn inverse(&self) -> Pose<T, CURR, PREV> {
+        let mut rpr = <Matrix<T, 4, 4>>::new();
+        for i in 0..3 {
+            let mut t = T::zero();
+            for j in 0..3 {
+                rpr[(i, j)] = self.rpr[(j, i)].clone();
+                t -= self.rpr[(j, i)].clone() * self.rpr[(j, 3)].clone();
+            }
+            rpr[(i, 3)] = t;
+        }
+        rpr[(3, 3)] = T::one();
+        Pose { rpr }
+    }
+    /// Expresses a direction given in frame `PREV` in frame `CURR`, the reverse of
+    /// `&pose * direction`.
+    pub fn inverse_transform(&self, direction: Direction<T, PREV>) -> Direction<T, CURR> {
+        &self.inverse() * direction
+    }
+    /// Expresses a point given in frame `PREV` in frame `CURR`, the reverse of `&pose * point`.
+    pub fn inverse_transform_point(&self, point: Point<T, PREV>) -> Point<T, CURR> {
+        &self.inverse() * point
+    }
     pub fn base_vectors(&self) -> (Vector<T, 3>, Vector<T, 3>, Vector<T, 3>, Vector<T, 3>) {
-        let (_rot, origin) = (*self).clone().decompose();
-        let origin: Coord<T, CURR> = origin.into();
-        let origin: Vector<T, 3> = (self * origin).into();
+        let origin: Vector<T, 3> = (self * Point::<T, CURR>::origin()).into();
 
-        let x: Coord<T, CURR> =
-            Vector::new_from_data([T::one(), T::zero(), T::zero()].clone()).into();
-        let y: Coord<T, CURR> =
-            Vector::new_from_data([T::zero(), T::one(), T::zero()].clone()).into();
-        let z: Coord<T, CURR> =
-            Vector::new_from_data([T::zero(), T::zero(), T::one()].clone()).into();
+        let x = Direction::<T, CURR>::new(T::one(), T::zero(), T::zero());
+        let y = Direction::<T, CURR>::new(T::zero(), T::one(), T::zero());
+        let z = Direction::<T, CURR>::new(T::zero(), T::zero(), T::one());
 
         let x: Vector<T, 3> = (self * x).into();
         let y: Vector<T, 3> = (self * y).into();
@@ -132,6 +276,204 @@ impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize> Pose<T,
     }
 }
 
+/// Ways in which a [`Pose`] can fail to be an element of SE(3).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PoseError {
+    /// `R^T R` is not the identity.
+    NotOrthonormal,
+    /// The rotation has determinant -1.
+    Reflection,
+    /// The bottom row is not `[0 0 0 1]`.
+    InvalidBottomRow,
+}
+
+impl Display for PoseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NotOrthonormal => write!(f, "the rotation is not orthonormal"),
+            Self::Reflection => write!(f, "the rotation is a reflection"),
+            Self::InvalidBottomRow => write!(f, "the bottom row is not [0 0 0 1]"),
+        }
+    }
+}
+
+impl std::error::Error for PoseError {}
+
+impl<T: CompliantNumerical + Trig + Float, const PREV: usize, const CURR: usize>
+    Pose<T, PREV, CURR>
+{
+    /// Checks that the pose is a valid element of SE(3), with every element within `tolerance`.
+    pub fn validate(&self, tolerance: T) -> Result<(), PoseError> {
+        let close = |a: T, b: T| (a - b).abs() <= tolerance;
+        for col in 0..4 {
+            let expected = if col == 3 { T::one() } else { T::zero() };
+            if !close(self.rpr[(3, col)], expected) {
+                return Err(PoseError::InvalidBottomRow);
+            }
+        }
+        for i in 0..3 {
+            for j in 0..3 {
+                let dot = (0..3).fold(T::zero(), |acc, k| {
+                    acc + self.rpr[(k, i)] * self.rpr[(k, j)]
+                });
+                let expected = if i == j { T::one() } else { T::zero() };
+                if !close(dot, expected) {
+                    return Err(PoseError::NotOrthonormal);
+                }
+            }
+        }
+        let r = |i: usize, j: usize| self.rpr[(i, j)];
+        let det = r(0, 0) * (r(1, 1) * r(2, 2) - r(1, 2) * r(2, 1))
+            - r(0, 1) * (r(1, 0) * r(2, 2) - r(1, 2) * r(2, 0))
+            + r(0, 2) * (r(1, 0) * r(2, 1) - r(1, 1) * r(2, 0));
+        match close(det, T::one()) {
+            true => Ok(()),
+            false => Err(PoseError::Reflection),
+        }
+    }
+    pub fn is_valid(&self, tolerance: T) -> bool {
+        self.validate(tolerance).is_ok()
+    }
+    /// Projects the rotation back onto SO(3) and resets the bottom row, for poses that have
+    /// drifted after many multiplications.
+    ///
+    /// Uses Gram-Schmidt on the columns, so the x axis keeps its direction, the y axis is made
+    /// orthogonal to it and the z axis is their cross product.
+    pub fn orthonormalize(&self) -> Self {
+        let col = |j: usize| [self.rpr[(0, j)], self.rpr[(1, j)], self.rpr[(2, j)]];
+        let dot = |a: [T; 3], b: [T; 3]| a[0] * b[0] + a[1] * b[1] + a[2] * b[2];
+        let normalize = |a: [T; 3]| {
+            let norm = dot(a, a).sqrt();
+            a.map(|el| el / norm)
+        };
+
+        let x = normalize(col(0));
+        let y = col(1);
+        let proj = dot(x, y);
+        let y = normalize([y[0] - proj * x[0], y[1] - proj * x[1], y[2] - proj * x[2]]);
+        let z = [
+            x[1] * y[2] - x[2] * y[1],
+            x[2] * y[0] - x[0] * y[2],
+            x[0] * y[1] - x[1] * y[0],
+        ];
+
+        let mut rpr = self.rpr;
+        for (j, axis) in [x, y, z].iter().enumerate() {
+            for (i, el) in axis.iter().enumerate() {
+                rpr[(i, j)] = *el;
+            }
+        }
+        for col in 0..3 {
+            rpr[(3, col)] = T::zero();
+        }
+        rpr[(3, 3)] = T::one();
+        Self { rpr }
+    }
+}
+
+impl<T: CompliantNumerical + Trig + Float, const PREV: usize, const CURR: usize>
+    Pose<T, PREV, CURR>
+{
+    fn from_parts(rot: Matrix<T, 3, 3>, t: [T; 3]) -> Self {
+        let mut ret = Self::rot(rot);
+        for (i, el) in t.into_iter().enumerate() {
+            ret.rpr[(i, 3)] = el;
+        }
+        ret
+    }
+    /// Interpolates between the poses, `s = 0` gives `self` and `s = 1` gives `other`.
+    ///
+    /// The rotation follows the shortest arc at constant angular velocity and the origin moves
+    /// along a straight line. For motion along the connecting screw, use
+    /// [`DualQuaternion::sclerp`](crate::dual_quaternion::DualQuaternion::sclerp).
+    pub fn interpolate(&self, other: &Self, s: T) -> Self {
+        let rot = Quaternion::from(self)
+            .slerp(&Quaternion::from(other), s)
+            .to_matrix();
+        let (a, b) = (self.translation(), other.translation());
+        Self::from_parts(rot, [0, 1, 2].map(|i| a[i] + (b[i] - a[i]) * s))
+    }
+    /// Returns the twist taking `self` to `other`, expressed in frame `CURR` of `self`, i.e.
+    /// `other = self * exp(error)`. It is zero when the poses are equal.
+    pub fn error(&self, other: &Self) -> Twist<T> {
+        (self.inverse() * other.clone()).log()
+    }
+    /// Returns the angle of the rotation between the poses, in `[0, pi]`.
+    pub fn rotation_distance(&self, other: &Self) -> T {
+        let rot = self.rotation().transpose() * other.rotation();
+        let w = so3_log(&rot);
+        (w[0] * w[0] + w[1] * w[1] + w[2] * w[2]).sqrt()
+    }
+    /// Returns the distance between the origins of the poses.
+    pub fn translation_distance(&self, other: &Self) -> T {
+        let (a, b) = (self.translation(), other.translation());
+        (0..3)
+            .fold(T::zero(), |acc, i| acc + (a[i] - b[i]) * (a[i] - b[i]))
+            .sqrt()
+    }
+    /// Combines the translation and rotation distances, `sqrt(t^2 + (length * angle)^2)`.
+    ///
+    /// `length` converts radians to units of length, a characteristic length such as the reach
+    /// of the robot weighs a rotation by how far it moves points on the robot.
+    pub fn distance(&self, other: &Self, length: T) -> T {
+        let t = self.translation_distance(other);
+        let r = self.rotation_distance(other) * length;
+        (t * t + r * r).sqrt()
+    }
+    /// Returns the mean of the poses, `None` if there are none.
+    ///
+    /// The translation is the arithmetic mean and the rotation the normalized mean of the
+    /// quaternions, which minimizes the chordal distance between the quaternions.
+    pub fn chordal_mean(poses: &[Self]) -> Option<Self> {
+        let first = Quaternion::from(poses.first()?);
+        let n = T::from(poses.len()).unwrap();
+        let mut sum = [T::zero(); 4];
+        let mut t = [T::zero(); 3];
+        for pose in poses {
+            let q = Quaternion::from(pose);
+            // q and -q are the same rotation, keep them in the same hemisphere
+            let sign = match q.dot(&first) < T::zero() {
+                true => -T::one(),
+                false => T::one(),
+            };
+            for (acc, el) in sum.iter_mut().zip([q.w, q.x, q.y, q.z]) {
+                *acc += el * sign;
+            }
+            let translation = pose.translation();
+            for (i, acc) in t.iter_mut().enumerate() {
+                *acc += translation[i] / n;
+            }
+        }
+        let q = Quaternion::new(sum[0], sum[1], sum[2], sum[3]).normalize();
+        Some(Self::from_parts(q.to_matrix(), t))
+    }
+    /// Returns the mean of the poses, `None` if there are none.
+    ///
+    /// The translation is the arithmetic mean and the rotation the Karcher mean, the rotation
+    /// minimizing the sum of squared rotation angles to the poses. It is found iteratively,
+    /// starting from [`Pose::chordal_mean`].
+    pub fn geodesic_mean(poses: &[Self]) -> Option<Self> {
+        let start = Self::chordal_mean(poses)?;
+        let n = T::from(poses.len()).unwrap();
+        let mut mean = start.rotation();
+        for _ in 0..20 {
+            let mut step = [T::zero(); 3];
+            for pose in poses {
+                let w = so3_log(&(mean.transpose() * pose.rotation()));
+                for (i, acc) in step.iter_mut().enumerate() {
+                    *acc += w[i] / n;
+                }
+            }
+            mean = mean * so3_exp(&Vector::new_from_data(step));
+            if step.iter().all(|el| el.abs() < T::epsilon()) {
+                break;
+            }
+        }
+        let t = start.translation();
+        Some(Self::from_parts(mean, [t[0], t[1], t[2]]))
+    }
+}
+
 impl<T: CompliantNumerical + Trig, const CURR: usize> Pose<T, 0, CURR> {
     /// Returns the forward kinematics for the system.
     pub fn fk(self) -> Vector<T, 3> {
@@ -144,25 +486,17 @@ impl<T: CompliantNumerical + Trig, const CURR: usize> Pose<T, 0, CURR> {
     }
 }
 
-impl<T: CompliantNumerical + Trig, const FRAME: usize> From<Vector<T, 3>> for Coord<T, FRAME> {
-    fn from(value: Vector<T, 3>) -> Self {
-        let data = [
-            value[0].clone(),
-            value[1].clone(),
-            value[2].clone(),
-            T::zero(),
-        ];
-        Self {
-            rpr: Vector::new_from_data(data),
-        }
-    }
-}
-impl<T: CompliantNumerical + Trig, const FRAME: usize> From<Coord<T, FRAME>> for Vector<T, 3> {
-    fn from(val: Coord<T, FRAME>) -> Self {
+impl<T: CompliantNumerical, const FRAME: usize> From<Direction<T, FRAME>> for Vector<T, 3> {
+    fn from(val: Direction<T, FRAME>) -> Self {
         let data = [val.rpr[0].clone(), val.rpr[1].clone(), val.rpr[2].clone()];
         Vector::new_from_data(data)
     }
 }
+impl<T: CompliantNumerical, const FRAME: usize> From<Point<T, FRAME>> for Vector<T, 3> {
+    fn from(val: Point<T, FRAME>) -> Self {
+        Vector::new_from_data([val.rpr[0].clone(), val.rpr[1].clone(), val.rpr[2].clone()])
+    }
+}
 impl<T: CompliantNumerical + Trig + std::fmt::Display, const PREV: usize, const CURR: usize> Display
     for Pose<T, PREV, CURR>
 where
@@ -195,24 +529,24 @@ impl<
         TOther: CompliantNumerical + Trig,
         const PREV: usize,
         const CURR: usize,
-    > core::ops::Mul<Coord<TOther, CURR>> for &Pose<T, PREV, CURR>
+    > core::ops::Mul<Direction<TOther, CURR>> for &Pose<T, PREV, CURR>
 {
-    type Output = Coord<T, PREV>;
-    fn mul(self, rhs: Coord<TOther, CURR>) -> Self::Output {
-        Coord {
+    type Output = Direction<T, PREV>;
+    fn mul(self, rhs: Direction<TOther, CURR>) -> Self::Output {
+        Direction {
             rpr: self.rpr.clone() * rhs.rpr,
         }
     }
 }
-impl<T: CompliantNumerical + Trig, const REF: usize> core::ops::Add for Coord<T, REF> {
+impl<T: CompliantNumerical, const REF: usize> core::ops::Add for Direction<T, REF> {
     type Output = Self;
     fn add(self, rhs: Self) -> Self::Output {
-        Coord {
+        Direction {
             rpr: self.rpr + rhs.rpr,
         }
     }
 }
-impl<T: CompliantNumerical + Trig, const REF: usize> core::ops::Sub for Coord<T, REF> {
+impl<T: CompliantNumerical, const REF: usize> core::ops::Sub for Direction<T, REF> {
     type Output = Self;
     fn sub(self, rhs: Self) -> Self::Output {
         Self {
@@ -221,6 +555,57 @@ impl<T: CompliantNumerical + Trig, const REF: usize> core::ops::Sub for Coord<T,
     }
 }
 
+impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize>
+    core::ops::Mul<Point<T, CURR>> for &Pose<T, PREV, CURR>
+{
+    type Output = Point<T, PREV>;
+    fn mul(self, rhs: Point<T, CURR>) -> Self::Output {
+        Point {
+            rpr: self.rpr.clone() * rhs.rpr,
+        }
+    }
+}
+
+impl<T: CompliantNumerical, const REF: usize> core::ops::Mul<T> for Direction<T, REF> {
+    type Output = Self;
+    fn mul(self, rhs: T) -> Self::Output {
+        Self {
+            rpr: Vector::new_from_data([0, 1, 2, 3].map(|i| self.rpr[i].clone() * rhs.clone())),
+        }
+    }
+}
+impl<T: CompliantNumerical, const REF: usize> core::ops::Neg for Direction<T, REF> {
+    type Output = Self;
+    fn neg(self) -> Self::Output {
+        self * (T::zero() - T::one())
+    }
+}
+
+/// The displacement from `rhs` to `self`.
+impl<T: CompliantNumerical, const REF: usize> core::ops::Sub for Point<T, REF> {
+    type Output = Direction<T, REF>;
+    fn sub(self, rhs: Self) -> Self::Output {
+        Direction {
+            rpr: self.rpr - rhs.rpr,
+        }
+    }
+}
+impl<T: CompliantNumerical, const REF: usize> core::ops::Add<Direction<T, REF>> for Point<T, REF> {
+    type Output = Self;
+    fn add(self, rhs: Direction<T, REF>) -> Self::Output {
+        Self {
+            rpr: self.rpr + rhs.rpr,
+        }
+    }
+}
+impl<T: CompliantNumerical, const REF: usize> core::ops::Sub<Direction<T, REF>> for Point<T, REF> {
+    type Output = Self;
+    fn sub(self, rhs: Direction<T, REF>) -> Self::Output {
+        Self {
+            rpr: self.rpr - rhs.rpr,
+        }
+    }
+}
 impl<'a, T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize>
     From<&'a Pose<T, PREV, CURR>> for &'a Matrix<T, 4, 4>
 {
@@ -235,3 +620,53 @@ impl<'a, T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize>
         &mut val.rpr
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pose() -> Pose<f32, 0, 1> {
+        let rot = Pose::<f32, 0, 1>::rot(rotz(std::f32::consts::FRAC_PI_2).unwrap());
+        let translation = Pose::<f32, 1, 1>::from_translation(Vector::new_from_data([1., 2., 3.]));
+        rot * translation
+    }
+
+    fn assert_close(lhs: Vector<f32, 3>, rhs: [f32; 3]) {
+        for (i, rhs) in rhs.iter().enumerate() {
+            assert!((lhs[i] - rhs).abs() < 1e-6, "{} != {rhs}", lhs[i]);
+        }
+    }
+
+    #[test]
+    fn translation_only_moves_points() {
+        let pose = Pose::<f32, 0, 1>::from_translation(Vector::new_from_data([1., 2., 3.]));
+        let point: Vector<f32, 3> = (&pose * Point::new(1., 0., 0.)).into();
+        let direction: Vector<f32, 3> = (&pose * Direction::new(1., 0., 0.)).into();
+        assert_close(point, [2., 2., 3.]);
+        assert_close(direction, [1., 0., 0.]);
+    }
+
+    #[test]
+    fn point_arithmetic() {
+        let (a, b) = (Point::<f32, 0>::new(1., 2., 3.), Point::new(4., 6., 3.));
+        let displacement = b.clone() - a.clone();
+        assert_eq!(displacement.norm(), 5.);
+        let moved: Vector<f32, 3> = (a + displacement).into();
+        assert_close(moved, [4., 6., 3.]);
+        // The displacement between two points is not affected by translation
+        let pose = pose();
+        let (a, b) = (Point::<f32, 1>::new(0., 0., 0.), Point::new(1., 0., 0.));
+        let lhs: Vector<f32, 3> = (&pose * b.clone() - &pose * a.clone()).into();
+        let rhs: Vector<f32, 3> = (&pose * (b - a)).into();
+        assert_close(lhs, [rhs[0], rhs[1], rhs[2]]);
+    }
+
+    #[test]
+    fn inverse_transform_round_trips() {
+        let pose = pose();
+        let point = pose.inverse_transform_point(&pose * Point::<f32, 1>::new(0.5, -1., 2.));
+        assert_close(point.into(), [0.5, -1., 2.]);
+        let direction = pose.inverse_transform(&pose * Direction::<f32, 1>::new(0.5, -1., 2.));
+        assert_close(direction.into(), [0.5, -1., 2.]);
+    }
+}
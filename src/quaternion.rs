@@ -0,0 +1,261 @@
+//! Unit quaternions for representing rotations.
+//!
+//! Quaternions are written `w + xi + yj + zk`, with `w` the scalar part. Rotations are only
+//! represented by unit quaternions, the constructors normalize where needed.
+use std::fmt::Display;
+use std::ops::Mul;
+
+use matrs::{matrix::helpers::rotations::Trig, CompliantNumerical, MatrixInterface, VectorTrait};
+use num_traits::Float;
+
+use crate::pose::Pose;
+use crate::{Matrix, Vector};
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quaternion<T: CompliantNumerical + Trig> {
+    pub w: T,
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+fn two<T: CompliantNumerical>() -> T {
+    T::one() + T::one()
+}
+
+impl<T: CompliantNumerical + Trig> Quaternion<T> {
+    /// Creates a quaternion from its components, it is not normalized.
+    pub fn new(w: T, x: T, y: T, z: T) -> Self {
+        Self { w, x, y, z }
+    }
+    pub fn identity() -> Self {
+        Self::new(T::one(), T::zero(), T::zero(), T::zero())
+    }
+    /// Creates the rotation of `angle` about `axis`, the axis does not need to be normalized.
+    pub fn from_axis_angle(axis: Vector<T, 3>, angle: T) -> Self {
+        let norm = T::sqrt(
+            axis[0].clone() * axis[0].clone()
+                + axis[1].clone() * axis[1].clone()
+                + axis[2].clone() * axis[2].clone(),
+        );
+        let half = angle / two();
+        let s = half.clone().sine() / norm;
+        Self::new(
+            half.cosine(),
+            axis[0].clone() * s.clone(),
+            axis[1].clone() * s.clone(),
+            axis[2].clone() * s,
+        )
+    }
+    pub fn dot(&self, other: &Self) -> T {
+        self.w.clone() * other.w.clone()
+            + self.x.clone() * other.x.clone()
+            + self.y.clone() * other.y.clone()
+            + self.z.clone() * other.z.clone()
+    }
+    pub fn norm(&self) -> T {
+        T::sqrt(self.dot(self))
+    }
+    pub fn normalize(&self) -> Self {
+        let norm = self.norm();
+        Self::new(
+            self.w.clone() / norm.clone(),
+            self.x.clone() / norm.clone(),
+            self.y.clone() / norm.clone(),
+            self.z.clone() / norm,
+        )
+    }
+    pub fn conjugate(&self) -> Self {
+        Self::new(
+            self.w.clone(),
+            T::zero() - self.x.clone(),
+            T::zero() - self.y.clone(),
+            T::zero() - self.z.clone(),
+        )
+    }
+    /// Returns the inverse rotation, for a unit quaternion this is the conjugate.
+    pub fn inverse(&self) -> Self {
+        self.conjugate()
+    }
+    /// Rotates a vector, i.e. computes `q v q*`.
+    pub fn rotate(&self, v: Vector<T, 3>) -> Vector<T, 3> {
+        let cross = |a: [T; 3], b: [T; 3]| {
+            [
+                a[1].clone() * b[2].clone() - a[2].clone() * b[1].clone(),
+                a[2].clone() * b[0].clone() - a[0].clone() * b[2].clone(),
+                a[0].clone() * b[1].clone() - a[1].clone() * b[0].clone(),
+            ]
+        };
+        let u = [self.x.clone(), self.y.clone(), self.z.clone()];
+        let v = [v[0].clone(), v[1].clone(), v[2].clone()];
+        // v + w t + u x t, with t = 2 u x v
+        let t = cross(u.clone(), v.clone()).map(|el| el * two());
+        let ut = cross(u, t.clone());
+        Vector::new_from_data(
+            [0, 1, 2].map(|i| v[i].clone() + self.w.clone() * t[i].clone() + ut[i].clone()),
+        )
+    }
+    /// Returns the rotation matrix, the quaternion is assumed to be of unit length.
+    pub fn to_matrix(&self) -> Matrix<T, 3, 3> {
+        let (w, x, y, z) = (
+            self.w.clone(),
+            self.x.clone(),
+            self.y.clone(),
+            self.z.clone(),
+        );
+        let sq = |a: &T| a.clone() * a.clone();
+        let prod = |a: &T, b: &T| two::<T>() * a.clone() * b.clone();
+        Matrix::new_from_data([
+            [
+                T::one() - two::<T>() * (sq(&y) + sq(&z)),
+                prod(&x, &y) - prod(&w, &z),
+                prod(&x, &z) + prod(&w, &y),
+            ],
+            [
+                prod(&x, &y) + prod(&w, &z),
+                T::one() - two::<T>() * (sq(&x) + sq(&z)),
+                prod(&y, &z) - prod(&w, &x),
+            ],
+            [
+                prod(&x, &z) - prod(&w, &y),
+                prod(&y, &z) + prod(&w, &x),
+                T::one() - two::<T>() * (sq(&x) + sq(&y)),
+            ],
+        ])
+    }
+}
+
+impl<T: CompliantNumerical + Trig + Float> Quaternion<T> {
+    /// Spherical linear interpolation, `t = 0` gives `self` and `t = 1` gives `other`.
+    ///
+    /// Interpolates along the shortest path, falling back to normalized linear interpolation
+    /// when the rotations are close.
+    pub fn slerp(&self, other: &Self, t: T) -> Self {
+        let mut other = other.clone();
+        let mut dot = self.dot(&other);
+        if dot < T::zero() {
+            other = Self::new(-other.w, -other.x, -other.y, -other.z);
+            dot = -dot;
+        }
+        let lerp = |a: &Self, b: &Self, wa: T, wb: T| {
+            Self::new(
+                a.w * wa + b.w * wb,
+                a.x * wa + b.x * wb,
+                a.y * wa + b.y * wb,
+                a.z * wa + b.z * wb,
+            )
+        };
+        if dot > T::one() - T::from(1e-6).unwrap() {
+            return lerp(self, &other, T::one() - t, t).normalize();
+        }
+        let theta = dot.acos();
+        let sin = theta.sin();
+        lerp(
+            self,
+            &other,
+            ((T::one() - t) * theta).sin() / sin,
+            (t * theta).sin() / sin,
+        )
+    }
+}
+
+/// The Hamilton product, `a * b` applies `b` first and then `a`.
+impl<T: CompliantNumerical + Trig> Mul for Quaternion<T> {
+    type Output = Self;
+    fn mul(self, rhs: Self) -> Self::Output {
+        let (w1, x1, y1, z1) = (self.w, self.x, self.y, self.z);
+        let (w2, x2, y2, z2) = (rhs.w, rhs.x, rhs.y, rhs.z);
+        Self::new(
+            w1.clone() * w2.clone()
+                - x1.clone() * x2.clone()
+                - y1.clone() * y2.clone()
+                - z1.clone() * z2.clone(),
+            w1.clone() * x2.clone() + x1.clone() * w2.clone() + y1.clone() * z2.clone()
+                - z1.clone() * y2.clone(),
+            w1.clone() * y2.clone() - x1.clone() * z2.clone()
+                + y1.clone() * w2.clone()
+                + z1.clone() * x2.clone(),
+            w1 * z2 + x1 * y2 - y1 * x2 + z1 * w2,
+        )
+    }
+}
+
+/// Converts a rotation matrix using Shepperd's method, which picks the numerically stable branch.
+impl<T: CompliantNumerical + Trig + PartialOrd> From<Matrix<T, 3, 3>> for Quaternion<T> {
+    fn from(m: Matrix<T, 3, 3>) -> Self {
+        let m = |i: usize, j: usize| m[(i, j)].clone();
+        let four = two::<T>() * two();
+        let trace = m(0, 0) + m(1, 1) + m(2, 2);
+        let q = if trace > T::zero() {
+            let s = T::sqrt(trace + T::one()) * two();
+            Self::new(
+                s.clone() / four,
+                (m(2, 1) - m(1, 2)) / s.clone(),
+                (m(0, 2) - m(2, 0)) / s.clone(),
+                (m(1, 0) - m(0, 1)) / s,
+            )
+        } else if m(0, 0) > m(1, 1) && m(0, 0) > m(2, 2) {
+            let s = T::sqrt(T::one() + m(0, 0) - m(1, 1) - m(2, 2)) * two();
+            Self::new(
+                (m(2, 1) - m(1, 2)) / s.clone(),
+                s.clone() / four,
+                (m(0, 1) + m(1, 0)) / s.clone(),
+                (m(0, 2) + m(2, 0)) / s,
+            )
+        } else if m(1, 1) > m(2, 2) {
+            let s = T::sqrt(T::one() + m(1, 1) - m(0, 0) - m(2, 2)) * two();
+            Self::new(
+                (m(0, 2) - m(2, 0)) / s.clone(),
+                (m(0, 1) + m(1, 0)) / s.clone(),
+                s.clone() / four,
+                (m(1, 2) + m(2, 1)) / s,
+            )
+        } else {
+            let s = T::sqrt(T::one() + m(2, 2) - m(0, 0) - m(1, 1)) * two();
+            Self::new(
+                (m(1, 0) - m(0, 1)) / s.clone(),
+                (m(0, 2) + m(2, 0)) / s.clone(),
+                (m(1, 2) + m(2, 1)) / s.clone(),
+                s / four,
+            )
+        };
+        q.normalize()
+    }
+}
+
+impl<T: CompliantNumerical + Trig> From<Quaternion<T>> for Matrix<T, 3, 3> {
+    fn from(value: Quaternion<T>) -> Self {
+        value.to_matrix()
+    }
+}
+
+/// Creates a pure rotation.
+impl<T: CompliantNumerical + Trig, const PREV: usize, const CURR: usize> From<Quaternion<T>>
+    for Pose<T, PREV, CURR>
+{
+    fn from(value: Quaternion<T>) -> Self {
+        Pose::rot(value.to_matrix())
+    }
+}
+
+/// Extracts the rotation of the pose, the translation is dropped.
+impl<T: CompliantNumerical + Trig + PartialOrd, const PREV: usize, const CURR: usize>
+    From<&Pose<T, PREV, CURR>> for Quaternion<T>
+{
+    fn from(value: &Pose<T, PREV, CURR>) -> Self {
+        let m: &Matrix<T, 4, 4> = value.into();
+        let mut rot = <Matrix<T, 3, 3>>::new();
+        for i in 0..3 {
+            for j in 0..3 {
+                rot[(i, j)] = m[(i, j)].clone();
+            }
+        }
+        rot.into()
+    }
+}
+
+impl<T: CompliantNumerical + Trig + Display> Display for Quaternion<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} + {}i + {}j + {}k", self.w, self.x, self.y, self.z)
+    }
+}
@@ -1,9 +1,13 @@
+pub mod cse;
 pub mod display;
 pub mod opt;
+pub mod sympy;
 use core::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign};
 
 use matrs::{matrix::rotations::Trig, CompliantNumerical};
+pub use cse::*;
 pub use opt::*;
+pub use sympy::ParseError;
 
 #[derive(Clone, Debug, PartialEq, PartialOrd)]
 pub enum Operation {
@@ -34,6 +38,96 @@ pub enum Sym {
     Constant(Constant),
 }
 
+impl Sym {
+    /// Creates an identifier from a name only known at runtime.
+    ///
+    /// Identifiers are `&'static str`, so the name is leaked. This is intended for the handful
+    /// of names a program generates, not for use in loops.
+    pub fn identifier(name: String) -> Self {
+        Self::Identifier(Box::leak(name.into_boxed_str()))
+    }
+    /// Replaces every occurrence of the identifier with `value`.
+    ///
+    /// The expression is rebuilt with the arithmetic operators, so numbers are folded, i.e.
+    /// substituting `q_1 = 0` in `q_1 + pi/2` gives `pi/2`.
+    pub fn subs(&self, identifier: &str, value: &Sym) -> Self {
+        let op = match self {
+            Self::Identifier(i) if *i == identifier => return value.clone(),
+            Self::Operation(op) => op,
+            _ => return self.clone(),
+        };
+        let subs = |s: &Sym| s.subs(identifier, value);
+        match &**op {
+            Operation::Sqrt(s) => Sym::sqrt(subs(s)),
+            Operation::Add(s1, s2) => subs(s1) + subs(s2),
+            Operation::Sum(els) => els.iter().map(subs).fold(Self::Number(0f32), |acc, s| acc + s),
+            Operation::Sub(s1, s2) => subs(s1) - subs(s2),
+            Operation::UnSub(s) => subs(s).sing_inversion(),
+            Operation::Div(s1, s2) => subs(s1) / subs(s2),
+            Operation::Mul(s1, s2) => subs(s1) * subs(s2),
+            Operation::Prod(els) => els.iter().map(subs).fold(Self::Number(1f32), |acc, s| acc * s),
+            Operation::Rem(s1, s2) => match (subs(s1), subs(s2)) {
+                (Self::Number(n1), Self::Number(n2)) => Self::Number(n1 % n2),
+                (s1, s2) => Self::Operation(Box::new(Operation::Rem(s1, s2))),
+            },
+            Operation::Cos(s) => match subs(s) {
+                Self::Number(n) => Self::Number(n.cos()),
+                s => Self::Operation(Box::new(Operation::Cos(s))),
+            },
+            Operation::Sin(s) => match subs(s) {
+                Self::Number(n) => Self::Number(n.sin()),
+                s => Self::Operation(Box::new(Operation::Sin(s))),
+            },
+            Operation::Nop(s) => subs(s),
+        }
+    }
+    /// Binding strength of the outermost operator, used by the exporters to only emit the
+    /// parentheses that are needed.
+    pub(crate) fn precedence(&self) -> u8 {
+        match self {
+            Self::Number(n) if *n < 0f32 => 2,
+            Self::Operation(o) => match &**o {
+                Operation::Add(_, _) | Operation::Sub(_, _) | Operation::Sum(_) => 1,
+                Operation::UnSub(_) => 2,
+                Operation::Mul(_, _)
+                | Operation::Div(_, _)
+                | Operation::Prod(_)
+                | Operation::Rem(_, _) => 3,
+                Operation::Nop(s) => s.precedence(),
+                _ => 4,
+            },
+            _ => 4,
+        }
+    }
+}
+
+impl Operation {
+    /// Returns the direct operands of the operation.
+    pub fn operands(&self) -> Vec<&Sym> {
+        match self {
+            Self::Sqrt(s) | Self::UnSub(s) | Self::Cos(s) | Self::Sin(s) | Self::Nop(s) => vec![s],
+            Self::Add(s1, s2)
+            | Self::Sub(s1, s2)
+            | Self::Div(s1, s2)
+            | Self::Mul(s1, s2)
+            | Self::Rem(s1, s2) => vec![s1, s2],
+            Self::Sum(els) | Self::Prod(els) => els.iter().collect(),
+        }
+    }
+    /// Returns mutable references to the direct operands of the operation.
+    pub fn operands_mut(&mut self) -> Vec<&mut Sym> {
+        match self {
+            Self::Sqrt(s) | Self::UnSub(s) | Self::Cos(s) | Self::Sin(s) | Self::Nop(s) => vec![s],
+            Self::Add(s1, s2)
+            | Self::Sub(s1, s2)
+            | Self::Div(s1, s2)
+            | Self::Mul(s1, s2)
+            | Self::Rem(s1, s2) => vec![s1, s2],
+            Self::Sum(els) | Self::Prod(els) => els.iter_mut().collect(),
+        }
+    }
+}
+
 pub trait SignInversion {
     fn sing_inversion(self) -> Self;
     fn negative(&self) -> bool;
@@ -0,0 +1,284 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use matrs::predule::Matrix;
+
+use crate::decore::decorators::{ToMatlab, ToTex};
+use crate::decore::matlab;
+use crate::pose::Pose;
+
+use super::{Operation, Sym};
+
+/// The result of common subexpression extraction.
+///
+/// `definitions` are ordered so that every intermediate only refers to the ones defined
+/// before it, `matrix` is the input rewritten in terms of the intermediates.
+#[derive(Clone, Debug)]
+pub struct Cse<const M: usize, const N: usize> {
+    pub definitions: Vec<(&'static str, Sym)>,
+    pub matrix: Matrix<Sym, M, N>,
+}
+
+pub trait ExtractCommon<const M: usize, const N: usize> {
+    /// Finds subexpressions that occur more than once and replaces them with intermediates
+    /// named `x_{1}`, `x_{2}`, ...
+    fn cse(&self) -> Cse<M, N>;
+}
+
+/// Whether or not the expression is worth naming.
+fn candidate(s: &Sym) -> bool {
+    match s {
+        Sym::Operation(o) => match **o {
+            Operation::Nop(_) => false,
+            Operation::UnSub(ref s) => matches!(s, Sym::Operation(_)),
+            _ => true,
+        },
+        _ => false,
+    }
+}
+
+/// Structural equality, unlike `==` NaNs are equal to each other so that every subtree is equal
+/// to itself.
+fn same(a: &Sym, b: &Sym) -> bool {
+    match (a, b) {
+        (Sym::Number(a), Sym::Number(b)) => a == b || (a.is_nan() && b.is_nan()),
+        (Sym::Operation(a), Sym::Operation(b)) => {
+            let (a, b) = (&**a, &**b);
+            let (lhs, rhs) = (a.operands(), b.operands());
+            std::mem::discriminant(a) == std::mem::discriminant(b)
+                && lhs.len() == rhs.len()
+                && lhs.into_iter().zip(rhs).all(|(a, b)| same(a, b))
+        }
+        _ => a == b,
+    }
+}
+
+/// A subtree keyed by a structural hash, consistent with [`same`].
+struct Key<'a> {
+    sym: &'a Sym,
+    hash: u64,
+}
+
+impl Hash for Key<'_> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.hash)
+    }
+}
+
+impl PartialEq for Key<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.hash == other.hash && same(self.sym, other.sym)
+    }
+}
+
+impl Eq for Key<'_> {}
+
+/// Subtrees in order of first occurrence, with the number of occurrences and their size.
+#[derive(Default)]
+struct Counts<'a> {
+    index: HashMap<Key<'a>, usize>,
+    subtrees: Vec<(&'a Sym, usize, usize)>,
+}
+
+/// Counts every candidate subtree, returning the structural hash and the size of `s`.
+///
+/// The hashes of the operands are reused for the operation, such that a pass is linear in the
+/// size of the expression.
+fn count<'a>(s: &'a Sym, counts: &mut Counts<'a>) -> (u64, usize) {
+    let mut hasher = DefaultHasher::new();
+    let mut size = 1;
+    std::mem::discriminant(s).hash(&mut hasher);
+    match s {
+        // Equal numbers have to hash equally, including 0 and -0 and all NaNs
+        Sym::Number(n) if n.is_nan() => f32::NAN.to_bits().hash(&mut hasher),
+        Sym::Number(n) if *n == 0. => 0u32.hash(&mut hasher),
+        Sym::Number(n) => n.to_bits().hash(&mut hasher),
+        Sym::Identifier(i) => i.hash(&mut hasher),
+        Sym::Constant(c) => std::mem::discriminant(c).hash(&mut hasher),
+        Sym::Operation(o) => {
+            std::mem::discriminant(&**o).hash(&mut hasher);
+            for operand in o.operands() {
+                let (hash, n) = count(operand, counts);
+                hash.hash(&mut hasher);
+                size += n;
+            }
+        }
+    }
+    let hash = hasher.finish();
+    if candidate(s) {
+        let next = counts.subtrees.len();
+        let idx = *counts.index.entry(Key { sym: s, hash }).or_insert(next);
+        match counts.subtrees.get_mut(idx) {
+            Some((_, n, _)) => *n += 1,
+            None => counts.subtrees.push((s, 1, size)),
+        }
+    }
+    (hash, size)
+}
+
+fn replace(s: &mut Sym, target: &Sym, with: &Sym) {
+    if same(s, target) {
+        *s = with.clone();
+        return;
+    }
+    if let Sym::Operation(o) = s {
+        for operand in o.operands_mut() {
+            replace(operand, target, with);
+        }
+    }
+}
+
+/// Renames identifiers according to `map`, all renames are applied simultaneously.
+fn rename(s: &mut Sym, map: &[(&'static str, &'static str)]) {
+    match s {
+        Sym::Identifier(i) => {
+            if let Some((_, new)) = map.iter().find(|(old, _)| old == i) {
+                *i = new;
+            }
+        }
+        Sym::Operation(o) => {
+            for operand in o.operands_mut() {
+                rename(operand, map);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn depends_on(s: &Sym, identifier: &'static str) -> bool {
+    match s {
+        Sym::Identifier(i) => *i == identifier,
+        Sym::Operation(o) => o.operands().into_iter().any(|s| depends_on(s, identifier)),
+        _ => false,
+    }
+}
+
+/// Orders the definitions such that no definition refers to a later one.
+fn sort_definitions(mut definitions: Vec<(&'static str, Sym)>) -> Vec<(&'static str, Sym)> {
+    let mut ret: Vec<(&'static str, Sym)> = Vec::new();
+    while !definitions.is_empty() {
+        let idx = definitions
+            .iter()
+            .position(|(_, s)| {
+                !definitions
+                    .iter()
+                    .any(|(identifier, _)| depends_on(s, identifier))
+            })
+            .expect("Definitions can not be cyclic");
+        ret.push(definitions.remove(idx));
+    }
+    ret
+}
+
+fn cse<const M: usize, const N: usize>(matrix: &Matrix<Sym, M, N>) -> Cse<M, N> {
+    let mut entries: Vec<Sym> = matrix
+        .get_elements()
+        .iter()
+        .flat_map(|row| row.iter().cloned())
+        .collect();
+    let mut definitions: Vec<(&'static str, Sym)> = Vec::new();
+
+    loop {
+        // Greedily name the largest repeated subtree, then recount since the replacement may
+        // have exposed or removed other repetitions.
+        let mut counts = Counts::default();
+        for s in entries.iter().chain(definitions.iter().map(|(_, s)| s)) {
+            count(s, &mut counts);
+        }
+        let target = match counts
+            .subtrees
+            .into_iter()
+            .filter(|(_, n, _)| *n > 1)
+            .max_by_key(|(_, _, size)| *size)
+        {
+            Some((s, _, _)) => s.clone(),
+            None => break,
+        };
+
+        let identifier = Sym::identifier(format!("x_{{{}}}", definitions.len() + 1));
+        for s in entries
+            .iter_mut()
+            .chain(definitions.iter_mut().map(|(_, s)| s))
+        {
+            replace(s, &target, &identifier);
+        }
+        if let Sym::Identifier(name) = identifier {
+            definitions.push((name, target));
+        }
+    }
+
+    // Number the intermediates in the order they are defined
+    let names: Vec<&'static str> = definitions.iter().map(|(name, _)| *name).collect();
+    let mut definitions = sort_definitions(definitions);
+    let map: Vec<(&'static str, &'static str)> = definitions
+        .iter()
+        .zip(names)
+        .map(|((old, _), new)| (*old, new))
+        .collect();
+    for (name, s) in definitions.iter_mut() {
+        rename(s, &map);
+        if let Some((_, new)) = map.iter().find(|(old, _)| old == name) {
+            *name = new;
+        }
+    }
+
+    let mut ret = matrix.clone();
+    for (idx, mut el) in entries.into_iter().enumerate() {
+        rename(&mut el, &map);
+        ret[(idx / N, idx % N)] = el;
+    }
+    Cse {
+        definitions,
+        matrix: ret,
+    }
+}
+
+impl<const M: usize, const N: usize> ExtractCommon<M, N> for Matrix<Sym, M, N> {
+    fn cse(&self) -> Cse<M, N> {
+        cse(self)
+    }
+}
+
+impl<const PREV: usize, const CURR: usize> ExtractCommon<4, 4> for Pose<Sym, PREV, CURR> {
+    fn cse(&self) -> Cse<4, 4> {
+        let intermediate: &Matrix<Sym, 4, 4> = self.into();
+        cse(intermediate)
+    }
+}
+
+impl<const M: usize, const N: usize> ToTex for Cse<M, N> {
+    fn to_tex_internal(&self, identifier: Option<&'static str>, first: bool) -> String {
+        let mut ret = String::new();
+        if !self.definitions.is_empty() {
+            ret += "\\begin{align*}\n";
+            let max = self.definitions.len() - 1;
+            for (idx, (name, s)) in self.definitions.iter().enumerate() {
+                ret += format!("\t{name} &= {}", s.to_tex(None).trim()).as_str();
+                if idx < max {
+                    ret += "\\\\";
+                }
+                ret += "\n";
+            }
+            ret += "\\end{align*}\n";
+        }
+        ret += self.matrix.to_tex_internal(identifier, first).as_str();
+        ret
+    }
+}
+
+impl<const M: usize, const N: usize> ToMatlab for Cse<M, N> {
+    fn to_matlab(&self, identifier: &'static str) -> String {
+        let mut ret = String::new();
+        for (name, s) in self.definitions.iter() {
+            ret += format!("{}\t=\t{};\n", matlab::identifier(name), matlab::expr(s)).as_str();
+        }
+        ret += format!("{}\t=\t[", matlab::identifier(identifier)).as_str();
+        for row in self.matrix.get_elements().iter() {
+            let row: Vec<String> = row.iter().map(matlab::expr).collect();
+            ret += format!("[{}];", row.join(",")).as_str();
+        }
+        ret += "];\n";
+        ret
+    }
+}
@@ -0,0 +1,584 @@
+//! Conversion between [`Sym`] and SymPy.
+//!
+//! The [`Display`] implementation of [`Sym`] already produces valid SymPy
+//! string syntax as long as identifiers are valid Python names, and [`FromStr`] parses the output
+//! of SymPy's `str` back. For identifiers such as `\theta_1`, use [`Sym::to_srepr`] and
+//! [`Sym::from_srepr`] which keep the names intact, i.e. the expression can be simplified in
+//! SymPy and brought back for TeX export.
+use std::fmt::Display;
+use std::str::FromStr;
+
+use super::{Constant, Operation, Sym};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    UnexpectedEnd,
+    UnexpectedToken(String),
+    UnknownFunction(String),
+    InvalidNumber(String),
+    /// Only square roots and integer powers of at most 16 in magnitude are converted to [`Sym`].
+    UnsupportedPower(String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::UnexpectedEnd => write!(f, "unexpected end of expression"),
+            Self::UnexpectedToken(t) => write!(f, "unexpected token {t}"),
+            Self::UnknownFunction(name) => write!(f, "unknown function {name}"),
+            Self::InvalidNumber(n) => write!(f, "invalid number {n}"),
+            Self::UnsupportedPower(p) => write!(f, "unsupported power {p}"),
+        }
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(String),
+    Name(String),
+    Str(String),
+    Punct(&'static str),
+}
+
+fn tokenize(s: &str) -> Result<Vec<Token>, ParseError> {
+    let mut ret = Vec::new();
+    let chars: Vec<char> = s.chars().collect();
+    let mut idx = 0;
+    while idx < chars.len() {
+        let c = chars[idx];
+        if c.is_whitespace() {
+            idx += 1;
+            continue;
+        }
+        if c.is_ascii_digit() || (c == '.' && chars.get(idx + 1).is_some_and(char::is_ascii_digit))
+        {
+            let start = idx;
+            while idx < chars.len() && (chars[idx].is_ascii_digit() || chars[idx] == '.') {
+                idx += 1;
+            }
+            // Exponent, e.g. 4.37e-08
+            if idx < chars.len() && (chars[idx] == 'e' || chars[idx] == 'E') {
+                idx += 1;
+                if idx < chars.len() && (chars[idx] == '-' || chars[idx] == '+') {
+                    idx += 1;
+                }
+                while idx < chars.len() && chars[idx].is_ascii_digit() {
+                    idx += 1;
+                }
+            }
+            ret.push(Token::Number(chars[start..idx].iter().collect()));
+            continue;
+        }
+        if c.is_alphabetic() || c == '_' || c == '\\' {
+            let start = idx;
+            while idx < chars.len()
+                && (chars[idx].is_alphanumeric() || matches!(chars[idx], '_' | '\\' | '{' | '}'))
+            {
+                idx += 1;
+            }
+            ret.push(Token::Name(chars[start..idx].iter().collect()));
+            continue;
+        }
+        if c == '\'' || c == '"' {
+            let mut val = String::new();
+            idx += 1;
+            loop {
+                match chars.get(idx) {
+                    None => return Err(ParseError::UnexpectedEnd),
+                    Some('\\') => {
+                        val.push(*chars.get(idx + 1).ok_or(ParseError::UnexpectedEnd)?);
+                        idx += 2;
+                    }
+                    Some(end) if *end == c => {
+                        idx += 1;
+                        break;
+                    }
+                    Some(el) => {
+                        val.push(*el);
+                        idx += 1;
+                    }
+                }
+            }
+            ret.push(Token::Str(val));
+            continue;
+        }
+        let punct = match (c, chars.get(idx + 1)) {
+            ('*', Some('*')) => "**",
+            ('+', _) => "+",
+            ('-', _) => "-",
+            ('*', _) => "*",
+            ('/', _) => "/",
+            ('%', _) => "%",
+            ('(', _) => "(",
+            (')', _) => ")",
+            (',', _) => ",",
+            ('=', _) => "=",
+            (c, _) => return Err(ParseError::UnexpectedToken(c.to_string())),
+        };
+        idx += punct.len();
+        ret.push(Token::Punct(punct));
+    }
+    Ok(ret)
+}
+
+fn number(n: &str) -> Result<f32, ParseError> {
+    n.parse::<f32>()
+        .map_err(|_| ParseError::InvalidNumber(n.to_string()))
+}
+
+/// Integer value of a number, if it has one.
+fn integer(s: &Sym) -> Option<i32> {
+    match s {
+        Sym::Number(n) if n.fract() == 0f32 => Some(*n as i32),
+        Sym::Operation(o) => match &**o {
+            Operation::UnSub(s) => integer(s).and_then(i32::checked_neg),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn is_half(s: &Sym) -> bool {
+    match s {
+        Sym::Number(n) => *n == 0.5,
+        Sym::Operation(o) => matches!(
+            &**o,
+            Operation::Div(Sym::Number(n1), Sym::Number(n2)) if *n1 == 1f32 && *n2 == 2f32
+        ),
+        _ => false,
+    }
+}
+
+/// The largest integer power that is expanded to a product, larger powers are rejected rather
+/// than repeating the base without bound.
+const MAX_POWER: u32 = 16;
+
+/// Converts `base**exp` to products, divisions and square roots.
+fn pow(base: Sym, exp: Sym) -> Result<Sym, ParseError> {
+    let repeat = |n: u32| match n {
+        1 => base.clone(),
+        n => Sym::Operation(Box::new(Operation::Prod(vec![base.clone(); n as usize]))),
+    };
+    match integer(&exp) {
+        Some(n) if n.unsigned_abs() > MAX_POWER => {
+            return Err(ParseError::UnsupportedPower(exp.to_string()))
+        }
+        Some(0) => return Ok(Sym::Number(1f32)),
+        Some(n) if n > 0 => return Ok(repeat(n.unsigned_abs())),
+        Some(n) => {
+            return Ok(Sym::Operation(Box::new(Operation::Div(
+                Sym::Number(1f32),
+                repeat(n.unsigned_abs()),
+            ))))
+        }
+        None => {}
+    }
+    if is_half(&exp) {
+        return Ok(Sym::Operation(Box::new(Operation::Sqrt(base))));
+    }
+    Err(ParseError::UnsupportedPower(exp.to_string()))
+}
+
+fn function(name: &str, mut args: Vec<Sym>) -> Result<Sym, ParseError> {
+    let op = match (name, args.len()) {
+        ("sin", 1) => Operation::Sin(args.remove(0)),
+        ("cos", 1) => Operation::Cos(args.remove(0)),
+        ("sqrt", 1) => Operation::Sqrt(args.remove(0)),
+        ("Mod", 2) => {
+            let lhs = args.remove(0);
+            Operation::Rem(lhs, args.remove(0))
+        }
+        ("Pow", 2) => {
+            let base = args.remove(0);
+            return pow(base, args.remove(0));
+        }
+        _ => return Err(ParseError::UnknownFunction(name.to_string())),
+    };
+    Ok(Sym::Operation(Box::new(op)))
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    idx: usize,
+    /// Identifiers are leaked, so every name is only leaked once per parse.
+    names: Vec<&'static str>,
+}
+
+impl Parser {
+    fn new(s: &str) -> Result<Self, ParseError> {
+        Ok(Self {
+            tokens: tokenize(s)?,
+            idx: 0,
+            names: Vec::new(),
+        })
+    }
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.idx)
+    }
+    fn next(&mut self) -> Result<Token, ParseError> {
+        let ret = self.tokens.get(self.idx).cloned();
+        self.idx += 1;
+        ret.ok_or(ParseError::UnexpectedEnd)
+    }
+    fn eat(&mut self, punct: &'static str) -> bool {
+        if self.peek() == Some(&Token::Punct(punct)) {
+            self.idx += 1;
+            return true;
+        }
+        false
+    }
+    fn expect(&mut self, punct: &'static str) -> Result<(), ParseError> {
+        match self.next()? {
+            Token::Punct(p) if p == punct => Ok(()),
+            t => Err(ParseError::UnexpectedToken(format!("{t:?}"))),
+        }
+    }
+    fn finish(self, ret: Sym) -> Result<Sym, ParseError> {
+        match self.peek() {
+            None => Ok(ret),
+            Some(t) => Err(ParseError::UnexpectedToken(format!("{t:?}"))),
+        }
+    }
+    fn identifier(&mut self, name: String) -> Sym {
+        if let Some(name) = self.names.iter().find(|el| ***el == name) {
+            return Sym::Identifier(name);
+        }
+        let ret = Sym::identifier(name);
+        if let Sym::Identifier(name) = ret {
+            self.names.push(name);
+        }
+        ret
+    }
+
+    // String syntax, following the python operator precedence.
+
+    fn expr(&mut self) -> Result<Sym, ParseError> {
+        let mut ret = self.term()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Punct("+")) => Operation::Add,
+                Some(Token::Punct("-")) => Operation::Sub,
+                _ => return Ok(ret),
+            };
+            self.idx += 1;
+            ret = Sym::Operation(Box::new(op(ret, self.term()?)));
+        }
+    }
+    fn term(&mut self) -> Result<Sym, ParseError> {
+        let mut ret = self.unary()?;
+        loop {
+            let op = match self.peek() {
+                Some(Token::Punct("*")) => Operation::Mul,
+                Some(Token::Punct("/")) => Operation::Div,
+                Some(Token::Punct("%")) => Operation::Rem,
+                _ => return Ok(ret),
+            };
+            self.idx += 1;
+            ret = Sym::Operation(Box::new(op(ret, self.unary()?)));
+        }
+    }
+    fn unary(&mut self) -> Result<Sym, ParseError> {
+        if self.eat("+") {
+            return self.unary();
+        }
+        if self.eat("-") {
+            return Ok(match self.unary()? {
+                Sym::Number(n) => Sym::Number(-n),
+                s => Sym::Operation(Box::new(Operation::UnSub(s))),
+            });
+        }
+        let base = self.atom()?;
+        match self.eat("**") {
+            true => pow(base, self.unary()?),
+            false => Ok(base),
+        }
+    }
+    fn atom(&mut self) -> Result<Sym, ParseError> {
+        match self.next()? {
+            Token::Number(n) => Ok(Sym::Number(number(&n)?)),
+            Token::Name(name) if self.eat("(") => {
+                let mut args = vec![self.expr()?];
+                while self.eat(",") {
+                    args.push(self.expr()?);
+                }
+                self.expect(")")?;
+                function(&name, args)
+            }
+            Token::Name(name) if name == "pi" => Ok(Sym::Constant(Constant::Pi)),
+            Token::Name(name) => Ok(self.identifier(name)),
+            Token::Punct("(") => {
+                let ret = self.expr()?;
+                self.expect(")")?;
+                Ok(ret)
+            }
+            t => Err(ParseError::UnexpectedToken(format!("{t:?}"))),
+        }
+    }
+
+    // srepr syntax, i.e. nested constructor calls.
+
+    fn srepr(&mut self) -> Result<Sym, ParseError> {
+        let name = match self.next()? {
+            Token::Name(name) => name,
+            t => return Err(ParseError::UnexpectedToken(format!("{t:?}"))),
+        };
+        if name == "pi" {
+            return Ok(Sym::Constant(Constant::Pi));
+        }
+        self.expect("(")?;
+        let mut args = Vec::new();
+        let mut literals = Vec::new();
+        while !self.eat(")") {
+            match (self.peek().cloned(), self.tokens.get(self.idx + 1)) {
+                // Assumptions such as `real=True` do not matter here
+                (Some(Token::Name(_)), Some(Token::Punct("="))) => self.idx += 3,
+                (Some(Token::Str(s)), _) => {
+                    self.idx += 1;
+                    literals.push(s);
+                }
+                (Some(Token::Number(_)), _) | (Some(Token::Punct("-")), _) => {
+                    let negative = self.eat("-");
+                    match self.next()? {
+                        Token::Number(n) if negative => literals.push(format!("-{n}")),
+                        Token::Number(n) => literals.push(n),
+                        t => return Err(ParseError::UnexpectedToken(format!("{t:?}"))),
+                    }
+                }
+                _ => args.push(self.srepr()?),
+            }
+            self.eat(",");
+        }
+        let literal = |idx: usize| -> Result<f32, ParseError> {
+            number(literals.get(idx).ok_or(ParseError::UnexpectedEnd)?)
+        };
+        match name.as_str() {
+            "Symbol" => {
+                let name = literals.first().cloned().ok_or(ParseError::UnexpectedEnd)?;
+                Ok(self.identifier(name))
+            }
+            "Integer" | "Float" => Ok(Sym::Number(literal(0)?)),
+            "Rational" => Ok(Sym::Operation(Box::new(Operation::Div(
+                Sym::Number(literal(0)?),
+                Sym::Number(literal(1)?),
+            )))),
+            "Half" => Ok(Sym::Number(0.5)),
+            "One" => Ok(Sym::Number(1f32)),
+            "Zero" => Ok(Sym::Number(0f32)),
+            "NegativeOne" => Ok(Sym::Number(-1f32)),
+            "Add" => Ok(add(args)),
+            "Mul" => Ok(mul(args)),
+            _ => function(&name, args),
+        }
+    }
+}
+
+/// Rebuilds an `Add`, turning negated terms in to subtractions.
+fn add(args: Vec<Sym>) -> Sym {
+    let mut args = args.into_iter();
+    let mut ret = args.next().unwrap_or(Sym::Number(0f32));
+    for arg in args {
+        let op = match arg {
+            Sym::Operation(o) => match *o {
+                Operation::UnSub(s) => Operation::Sub(ret, s),
+                o => Operation::Add(ret, Sym::Operation(Box::new(o))),
+            },
+            Sym::Number(n) if n < 0f32 => Operation::Sub(ret, Sym::Number(-n)),
+            s => Operation::Add(ret, s),
+        };
+        ret = Sym::Operation(Box::new(op));
+    }
+    ret
+}
+
+/// Rebuilds a `Mul`, turning `-1` factors into negations and negative powers into divisions.
+fn mul(args: Vec<Sym>) -> Sym {
+    let mut negative = false;
+    let mut numerator = Vec::new();
+    let mut denominator = Vec::new();
+    for arg in args {
+        match arg {
+            Sym::Number(-1f32) => negative = !negative,
+            Sym::Number(n) if n < 0f32 => {
+                negative = !negative;
+                numerator.push(Sym::Number(-n));
+            }
+            Sym::Operation(o) => match *o {
+                Operation::Div(Sym::Number(1f32), s) => denominator.push(s),
+                o => numerator.push(Sym::Operation(Box::new(o))),
+            },
+            s => numerator.push(s),
+        }
+    }
+    let product = |mut els: Vec<Sym>| match els.len() {
+        0 => Sym::Number(1f32),
+        1 => els.remove(0),
+        2 => {
+            let lhs = els.remove(0);
+            Sym::Operation(Box::new(Operation::Mul(lhs, els.remove(0))))
+        }
+        _ => Sym::Operation(Box::new(Operation::Prod(els))),
+    };
+    let mut ret = product(numerator);
+    if !denominator.is_empty() {
+        ret = Sym::Operation(Box::new(Operation::Div(ret, product(denominator))));
+    }
+    match negative {
+        true => Sym::Operation(Box::new(Operation::UnSub(ret))),
+        false => ret,
+    }
+}
+
+impl FromStr for Sym {
+    type Err = ParseError;
+    /// Parses SymPy string syntax, e.g. `-a_1*sin(q_1)**2 + sqrt(2)*pi/2`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser::new(s)?;
+        let ret = parser.expr()?;
+        parser.finish(ret)
+    }
+}
+
+fn srepr_product(els: &[&Sym]) -> String {
+    format!(
+        "Mul({})",
+        els.iter()
+            .map(|s| s.to_srepr())
+            .collect::<Vec<String>>()
+            .join(", ")
+    )
+}
+
+impl Sym {
+    /// Converts the expression to SymPy `srepr` syntax.
+    pub fn to_srepr(&self) -> String {
+        match self {
+            Self::Number(n) if n.fract() == 0f32 => format!("Integer({})", *n as i64),
+            Self::Number(n) => format!("Float('{n}')"),
+            Self::Identifier(i) => {
+                format!("Symbol('{}')", i.replace('\\', "\\\\").replace('\'', "\\'"))
+            }
+            Self::Constant(Constant::Pi) => "pi".to_string(),
+            Self::Operation(o) => match &**o {
+                Operation::Sqrt(s) => format!("Pow({}, Rational(1, 2))", s.to_srepr()),
+                Operation::Sin(s) => format!("sin({})", s.to_srepr()),
+                Operation::Cos(s) => format!("cos({})", s.to_srepr()),
+                Operation::Nop(s) => s.to_srepr(),
+                Operation::UnSub(s) => format!("Mul(Integer(-1), {})", s.to_srepr()),
+                Operation::Add(s1, s2) => format!("Add({}, {})", s1.to_srepr(), s2.to_srepr()),
+                Operation::Sub(s1, s2) => format!(
+                    "Add({}, Mul(Integer(-1), {}))",
+                    s1.to_srepr(),
+                    s2.to_srepr()
+                ),
+                Operation::Sum(els) => format!(
+                    "Add({})",
+                    els.iter()
+                        .map(|s| s.to_srepr())
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                ),
+                Operation::Mul(s1, s2) => srepr_product(&[s1, s2]),
+                Operation::Prod(els) => srepr_product(&els.iter().collect::<Vec<&Sym>>()),
+                Operation::Div(s1, s2) => format!(
+                    "Mul({}, Pow({}, Integer(-1)))",
+                    s1.to_srepr(),
+                    s2.to_srepr()
+                ),
+                Operation::Rem(s1, s2) => format!("Mod({}, {})", s1.to_srepr(), s2.to_srepr()),
+            },
+        }
+    }
+
+    /// Parses SymPy `srepr` syntax, e.g. `Mul(Integer(2), Symbol('q_1'))`.
+    pub fn from_srepr(s: &str) -> Result<Self, ParseError> {
+        let mut parser = Parser::new(s)?;
+        let ret = parser.srepr()?;
+        parser.finish(ret)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn id(name: &'static str) -> Sym {
+        Sym::Identifier(name)
+    }
+
+    #[test]
+    fn parses_srepr() {
+        let s = Sym::from_srepr("Mul(Integer(2), Symbol('\\\\theta_1'))").unwrap();
+        assert_eq!(s.to_string(), "(2*\\theta_1)");
+        let s = Sym::from_srepr("Pow(Symbol('x'), Rational(1, 2))").unwrap();
+        assert_eq!(s, Sym::Operation(Box::new(Operation::Sqrt(id("x")))));
+        assert_eq!(Sym::from_srepr("pi").unwrap(), Sym::Constant(Constant::Pi));
+    }
+
+    #[test]
+    fn srepr_round_trip() {
+        for s in [
+            "Add(Mul(Integer(-1), Symbol('a_1'), sin(Symbol('q_1'))), Symbol('d_2'))",
+            "Mul(Symbol('a'), Pow(Symbol('b'), Integer(-1)))",
+            "Mul(Pow(Symbol('\\\\theta_1'), Integer(3)), cos(Mul(pi, Rational(1, 2))))",
+            "Mod(Symbol('x'), Integer(2))",
+            "Add(Float('0.5'), Pow(Symbol('l_1'), Rational(1, 2)))",
+        ] {
+            let parsed = Sym::from_srepr(s).unwrap();
+            let again = Sym::from_srepr(&parsed.to_srepr()).unwrap();
+            assert_eq!(parsed, again, "{s}");
+        }
+    }
+
+    #[test]
+    fn str_round_trip() {
+        for s in [
+            "-a_1*sin(q_1)**2 + sqrt(2)*pi/2",
+            "x**(-3) + Mod(y, 2)",
+            "cos(q_1 + q_2)*l_2 - 0.25",
+        ] {
+            // Products of powers print like nested products, so compare from the first reprint
+            let once: Sym = s.parse::<Sym>().unwrap().to_string().parse().unwrap();
+            let twice: Sym = once.to_string().parse().unwrap();
+            assert_eq!(once, twice, "{s}");
+        }
+    }
+
+    #[test]
+    fn identifiers_survive_srepr() {
+        let s = id("\\theta_1") * id("a'");
+        let back = Sym::from_srepr(&s.to_srepr()).unwrap();
+        let mut ids = Vec::new();
+        crate::decore::matlab::identifiers(&back, &mut ids);
+        assert_eq!(ids, ["\\theta_1", "a'"]);
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        for s in ["", "x +", "(x", "x)", "2 3", "foo(x)", "sin(x, y)", "1.2.3"] {
+            assert!(s.parse::<Sym>().is_err(), "{s}");
+        }
+        for s in [
+            "Mul(",
+            "Symbol(x)",
+            "Symbol('x'",
+            "Add(Integer(1),, Integer(2))",
+            "Unknown(Symbol('x'))",
+        ] {
+            assert!(Sym::from_srepr(s).is_err(), "{s}");
+        }
+    }
+
+    #[test]
+    fn bounds_powers() {
+        assert!("x**16".parse::<Sym>().is_ok());
+        for s in ["x**17", "x**-17", "x**-2147483648", "x**1e30", "x**0.3"] {
+            assert!(matches!(
+                s.parse::<Sym>(),
+                Err(ParseError::UnsupportedPower(_))
+            ));
+        }
+        assert_eq!("x**0".parse::<Sym>().unwrap(), Sym::Number(1.));
+    }
+}